@@ -0,0 +1,252 @@
+use std::{fs, hint::black_box, path::Path};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use gen_changelog::{ChangeLog, ChangeLogConfig, CherryPickPolicy, RustPackages};
+use git2::{Oid, Repository, Signature, Time};
+use tempfile::TempDir;
+
+/// Number of commits on the mainline of the generated repository
+const COMMITS: usize = 20_000;
+/// A release is tagged every `RELEASE_EVERY` mainline commits
+const RELEASE_EVERY: usize = 500;
+/// A two commit branch is merged every `MERGE_EVERY` mainline commits
+const MERGE_EVERY: usize = 25;
+
+const SUBJECTS: [&str; 6] = [
+    "feat: add a feature",
+    "fix: correct a bug",
+    "docs: describe a feature",
+    "chore(deps): update rust crate foo",
+    "refactor(core): tidy the parser",
+    "ci: speed up the pipeline",
+];
+
+/// Packages of the workspace, each commit changing the source of one of them
+const PACKAGES: [&str; 2] = ["foo", "bar"];
+
+const WORKSPACE_MANIFEST: &str = "[workspace]\nmembers = [\"crates/foo\", \"crates/bar\"]\n";
+
+fn package_manifest(name: &str) -> String {
+    format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2024\"\n")
+}
+
+/// Writes the workspace manifests to the working directory, so the packages
+/// can be read with [`RustPackages::new`]
+fn write_workspace(root: &Path) {
+    fs::write(root.join("Cargo.toml"), WORKSPACE_MANIFEST).unwrap();
+    for name in PACKAGES {
+        let src = root.join("crates").join(name).join("src");
+        fs::create_dir_all(&src).unwrap();
+        fs::write(src.join("lib.rs"), "").unwrap();
+        fs::write(
+            root.join("crates").join(name).join("Cargo.toml"),
+            package_manifest(name),
+        )
+        .unwrap();
+    }
+}
+
+/// Tree of the workspace with the given content for the source of each
+/// package
+fn workspace_tree(repo: &Repository, sources: &[String; 2]) -> Oid {
+    let tree = |entries: &[(&str, Oid, i32)]| {
+        let mut builder = repo.treebuilder(None).unwrap();
+        for (name, id, mode) in entries {
+            builder.insert(name, *id, *mode).unwrap();
+        }
+        builder.write().unwrap()
+    };
+    let blob = |content: &str| repo.blob(content.as_bytes()).unwrap();
+
+    let crates = PACKAGES
+        .iter()
+        .zip(sources)
+        .map(|(name, source)| {
+            let src = tree(&[("lib.rs", blob(source), 0o100644)]);
+            let manifest = blob(&package_manifest(name));
+            let package = tree(&[("Cargo.toml", manifest, 0o100644), ("src", src, 0o040000)]);
+            (*name, package, 0o040000)
+        })
+        .collect::<Vec<_>>();
+    let crates = tree(&crates);
+    tree(&[
+        ("Cargo.toml", blob(WORKSPACE_MANIFEST), 0o100644),
+        ("crates", crates, 0o040000),
+    ])
+}
+
+/// Generates a workspace repository with a long mainline history, merged
+/// branches, regular release tags and a release tagged on a maintenance
+/// branch.
+///
+/// Every commit changes the source of one of the packages, so each commit has
+/// a diff to compute as in a real history.
+fn fixture_repo() -> (TempDir, Repository) {
+    let temp_dir = TempDir::new().expect("create temp dir");
+    let repo = Repository::init(temp_dir.path()).expect("init repo");
+    repo.remote("origin", "https://github.com/jerus-org/fixture.git")
+        .expect("add remote");
+    write_workspace(temp_dir.path());
+
+    // Scope the objects borrowing `repo` so they drop before it is returned
+    {
+        let mut sources = [String::new(), String::new()];
+        let mut changes = 0;
+        // Changes the source of the next package and returns the new tree
+        let mut change = || {
+            changes += 1;
+            sources[changes % PACKAGES.len()] = format!("// change {changes}\n");
+            workspace_tree(&repo, &sources)
+        };
+
+        let mut seconds = 1_600_000_000;
+        let mut commit = |parents: &[Oid], tree: Oid, message: &str| {
+            seconds += 60;
+            let sig =
+                Signature::new("Jane Doe", "jane@example.com", &Time::new(seconds, 0)).unwrap();
+            let tree = repo.find_tree(tree).unwrap();
+            let parents = parents
+                .iter()
+                .map(|id| repo.find_commit(*id).unwrap())
+                .collect::<Vec<_>>();
+            let parents = parents.iter().collect::<Vec<_>>();
+            repo.commit(None, &sig, &sig, message, &tree, &parents)
+                .unwrap()
+        };
+
+        let mut head = commit(&[], change(), "feat: initial commit");
+        let mut releases = Vec::new();
+        for n in 1..COMMITS {
+            let subject = SUBJECTS[n % SUBJECTS.len()];
+            head = if n % MERGE_EVERY == 0 {
+                let wip = commit(&[head], change(), "wip");
+                let tree = change();
+                let branch = commit(&[wip], tree, subject);
+                commit(&[head, branch], tree, "Merge branch 'feature'")
+            } else {
+                commit(&[head], change(), subject)
+            };
+            if n % RELEASE_EVERY == 0 {
+                releases.push(head);
+            }
+        }
+
+        for (minor, id) in releases.iter().enumerate() {
+            let object = repo.find_object(*id, None).unwrap();
+            repo.tag_lightweight(&format!("v0.{minor}.0"), &object, false)
+                .unwrap();
+        }
+
+        // A patch release on a maintenance branch, not in the history of main
+        let latest = releases.len() - 1;
+        let patch = commit(&[releases[latest]], change(), "fix: backport a fix");
+        let object = repo.find_object(patch, None).unwrap();
+        repo.tag_lightweight(&format!("v0.{latest}.1"), &object, false)
+            .unwrap();
+        repo.reference("refs/heads/main", head, true, "main")
+            .unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+    }
+
+    (temp_dir, repo)
+}
+
+fn benchmark_walk_repository(c: &mut Criterion) {
+    let (temp_dir, repo) = fixture_repo();
+    let package = RustPackages::new(temp_dir.path())
+        .unwrap()
+        .packages_by_name
+        .get("foo")
+        .cloned();
+
+    let mut group = c.benchmark_group("walk_repository");
+    group.sample_size(10);
+
+    group.bench_function("all_releases", |b| {
+        b.iter(|| {
+            ChangeLog::builder()
+                .with_config(ChangeLogConfig::default())
+                .walk_repository(black_box(&repo))
+                .unwrap()
+                .build()
+        })
+    });
+
+    group.bench_function("all_releases_parallel", |b| {
+        b.iter(|| {
+            ChangeLog::builder()
+                .with_config(ChangeLogConfig::default())
+                .with_jobs(0)
+                .walk_repository(black_box(&repo))
+                .unwrap()
+                .build()
+        })
+    });
+
+    group.bench_function("latest_release", |b| {
+        let mut config = ChangeLogConfig::default();
+        config.set_display_sections(Some(2));
+        b.iter(|| {
+            ChangeLog::builder()
+                .with_config(config.clone())
+                .walk_repository(black_box(&repo))
+                .unwrap()
+                .build()
+        })
+    });
+
+    group.bench_function("first_parent", |b| {
+        let mut config = ChangeLogConfig::default();
+        config.set_first_parent(true);
+        b.iter(|| {
+            ChangeLog::builder()
+                .with_config(config.clone())
+                .walk_repository(black_box(&repo))
+                .unwrap()
+                .build()
+        })
+    });
+
+    // Filtering the commits for a package diffs every commit, with an empty
+    // diff cache on each iteration
+    group.bench_function("package", |b| {
+        b.iter(|| {
+            ChangeLog::builder()
+                .with_config(ChangeLogConfig::default())
+                .with_rust_package(package.clone())
+                .walk_repository(black_box(&repo))
+                .unwrap()
+                .build()
+        })
+    });
+
+    group.bench_function("package_parallel", |b| {
+        b.iter(|| {
+            ChangeLog::builder()
+                .with_config(ChangeLogConfig::default())
+                .with_rust_package(package.clone())
+                .with_jobs(0)
+                .walk_repository(black_box(&repo))
+                .unwrap()
+                .build()
+        })
+    });
+
+    // Finding cherry-picked changes computes the patch-id of every commit
+    group.bench_function("cherry_pick_earliest", |b| {
+        let mut config = ChangeLogConfig::default();
+        config.set_cherry_pick_policy(CherryPickPolicy::Earliest);
+        b.iter(|| {
+            ChangeLog::builder()
+                .with_config(config.clone())
+                .walk_repository(black_box(&repo))
+                .unwrap()
+                .build()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_walk_repository);
+criterion_main!(benches);
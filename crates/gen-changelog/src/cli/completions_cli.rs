@@ -0,0 +1,27 @@
+use std::io::Write;
+
+use clap::{CommandFactory, Parser};
+use clap_complete::Shell;
+use gen_changelog::Error;
+
+use crate::Cli;
+
+#[derive(Parser, Debug)]
+pub(crate) struct CompletionsCli {
+    /// Shell to generate the completion script for
+    #[arg(value_enum)]
+    shell: Shell,
+}
+
+impl CompletionsCli {
+    pub(crate) fn run(&self) -> Result<(), Error> {
+        log::debug!("Arguments to apply: {self:#?}");
+        let mut command = Cli::command();
+        let name = command.get_name().to_string();
+        // Write through a buffer, as the generator panics on a write error
+        let mut script = Vec::new();
+        clap_complete::generate(self.shell, &mut command, name, &mut script);
+        std::io::stdout().write_all(&script)?;
+        Ok(())
+    }
+}
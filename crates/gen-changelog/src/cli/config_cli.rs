@@ -1,33 +1,239 @@
-use clap::Parser;
-use gen_changelog::ChangeLogConfig;
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand};
+use gen_changelog::{
+    ChangeLogConfig, ConfigDocument, ConfigLayers, ConfigSource, Error, RustPackages, Severity,
+};
+use titlecase::Titlecase;
 
 const DEFAULT_CONFIG_FILE: &str = "gen-changelog.toml";
 
 #[derive(Parser, Debug)]
 pub(crate) struct ConfigCli {
-    /// Save the configuration to a file
+    /// Name of file for configuration [default: gen-changelog.toml]
+    #[arg(short, long, global = true)]
+    file: Option<String>,
+    #[command(subcommand)]
+    command: ConfigCommands,
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommands {
+    /// Print the configuration used to generate the changelog
+    #[clap(name = "show")]
+    Show(ShowArgs),
+    /// Check the configuration file for problems
+    #[clap(name = "validate")]
+    Validate,
+    /// Write a configuration file with the default settings
+    #[clap(name = "init")]
+    Init(InitArgs),
+    /// Print the value of a configuration key
+    #[clap(name = "get")]
+    Get(GetArgs),
+    /// Set the value of a configuration key in the file
+    #[clap(name = "set")]
+    Set(SetArgs),
+    /// Publish a group, defining it when commit types are given
+    #[clap(name = "add-group")]
+    AddGroup(AddGroupArgs),
+    /// Stop publishing a group
+    #[clap(name = "remove-group")]
+    RemoveGroup(RemoveGroupArgs),
+}
+
+#[derive(Parser, Debug)]
+struct ShowArgs {
+    /// Print the source of each value instead of the configuration
+    #[arg(long)]
+    origins: bool,
+    /// Include the configuration of a workspace package
     #[arg(short, long)]
-    save: bool,
-    /// Name of file for configuration
-    #[arg(short, long, default_value = DEFAULT_CONFIG_FILE)]
-    file: String,
-    /// Print the configuration
-    #[arg(short = 'p', long)]
-    show: bool,
+    package: Option<String>,
+    /// Path to the repository
+    #[arg(long, default_value = ".")]
+    repository_dir: String,
+}
+
+#[derive(Parser, Debug)]
+struct InitArgs {
+    /// Overwrite an existing configuration file
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(Parser, Debug)]
+struct GetArgs {
+    /// Dotted path to the key, e.g. `lint.max-subject-length`
+    key: String,
+}
+
+#[derive(Parser, Debug)]
+struct SetArgs {
+    /// Dotted path to the key, e.g. `lint.max-subject-length`
+    key: String,
+    /// Value as TOML, taken as a string when it is not valid TOML
+    value: String,
+}
+
+#[derive(Parser, Debug)]
+struct AddGroupArgs {
+    /// Name of the group
+    name: String,
+    /// Conventional commit types listed under the group
+    #[arg(short = 't', long, value_delimiter = ',')]
+    cc_types: Vec<String>,
+}
+
+#[derive(Parser, Debug)]
+struct RemoveGroupArgs {
+    /// Name of the group
+    name: String,
 }
 
 impl ConfigCli {
-    pub(crate) fn run(&self) -> Result<(), gen_changelog::Error> {
-        let mut config = ChangeLogConfig::default();
-        // setting a default number of sections of 3
-        config.set_display_sections(Some(3));
-        if self.save {
-            log::info!("Saving the default changelog configuration.");
-            config.save(Some(&self.file))?;
+    pub(crate) fn run(&self) -> Result<(), Error> {
+        log::debug!("Arguments to apply: {self:#?}");
+        let file = self.file.as_deref().unwrap_or(DEFAULT_CONFIG_FILE);
+
+        match &self.command {
+            ConfigCommands::Show(args) => args.run(self.file.as_deref())?,
+            ConfigCommands::Validate => validate(file)?,
+            ConfigCommands::Init(args) => {
+                if Path::new(file).exists() && !args.force {
+                    return Err(Error::ConfigExists(file.into()));
+                }
+                let mut config = ChangeLogConfig::default();
+                // setting a default number of sections of 3
+                config.set_display_sections(Some(3));
+                config.save(Some(file))?;
+                println!("Configuration written to {file}");
+            }
+            ConfigCommands::Get(args) => {
+                println!("{}", ConfigDocument::open(file)?.get(&args.key)?);
+            }
+            ConfigCommands::Set(args) => {
+                ConfigDocument::open(file)?
+                    .set(&args.key, &args.value)?
+                    .save()?;
+            }
+            ConfigCommands::AddGroup(args) => {
+                let mut document = ConfigDocument::open(file)?;
+                let mut config = document.config()?;
+                let name = args.name.titlecase();
+                if !args.cc_types.is_empty() {
+                    config.define_group(&name, &args.cc_types);
+                } else if config.has_group(&name) {
+                    config.publish_group(&name);
+                } else {
+                    return Err(Error::GroupNotFound(name));
+                }
+                document.update(&config)?.save()?;
+            }
+            ConfigCommands::RemoveGroup(args) => {
+                let mut document = ConfigDocument::open(file)?;
+                let mut config = document.config()?;
+                let name = args.name.titlecase();
+                if !config.has_group(&name) {
+                    return Err(Error::GroupNotFound(name));
+                }
+                config.unpublish_group(&name);
+                document.update(&config)?.save()?;
+            }
         }
-        if self.show {
+        Ok(())
+    }
+}
+
+impl ShowArgs {
+    fn run(&self, file: Option<&str>) -> Result<(), Error> {
+        let repository_dir = Path::new(&self.repository_dir);
+        let package_root = match &self.package {
+            Some(name) => Some(package_root(repository_dir, name)?),
+            None => None,
+        };
+        let layers = config_layers(file, repository_dir, package_root.as_deref())?;
+        let config = apply_defaults(layers.config()?);
+
+        if self.origins {
+            for (key, value, source) in layers.origins(&config)? {
+                println!("{key} = {value}  # {source}");
+            }
+        } else {
             config.save(None)?;
         }
         Ok(())
     }
 }
+
+/// Prints the problems found in the configuration file, failing when any is
+/// an error
+fn validate(file: &str) -> Result<(), Error> {
+    if !Path::new(file).is_file() {
+        return Err(Error::ConfigNotFound(file.into()));
+    }
+    let diagnostics = ChangeLogConfig::validate_str(&std::fs::read_to_string(file)?);
+    for diagnostic in &diagnostics {
+        println!("{file}:{diagnostic}");
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity() == Severity::Error)
+        .count();
+    if errors > 0 {
+        Err(Error::ConfigInvalid(errors))
+    } else {
+        println!("{file} is valid");
+        Ok(())
+    }
+}
+
+/// Loads the configuration for the repository and, when given, the package.
+///
+/// Security changes are always published.
+pub(crate) fn load_config(
+    file: Option<&str>,
+    repository_dir: &Path,
+    package_root: Option<&Path>,
+) -> Result<ChangeLogConfig, Error> {
+    let config = config_layers(file, repository_dir, package_root)?.config()?;
+    Ok(apply_defaults(config))
+}
+
+/// Layers the user, repository and package configuration files and Cargo
+/// metadata, or only the file named on the command line
+fn config_layers(
+    file: Option<&str>,
+    repository_dir: &Path,
+    package_root: Option<&Path>,
+) -> Result<ConfigLayers, Error> {
+    let Some(file) = file else {
+        return ConfigLayers::discover(
+            ConfigLayers::user_config_dir().as_deref(),
+            repository_dir,
+            package_root,
+        );
+    };
+    if !Path::new(file).is_file() {
+        return Err(Error::ConfigNotFound(file.into()));
+    }
+    let mut layers = ConfigLayers::new();
+    layers.add_file(ConfigSource::File(file.into()))?;
+    Ok(layers)
+}
+
+fn apply_defaults(mut config: ChangeLogConfig) -> ChangeLogConfig {
+    config.publish_group("Security");
+    config
+}
+
+/// Root directory of the workspace package
+fn package_root(repository_dir: &Path, name: &str) -> Result<PathBuf, Error> {
+    let packages = RustPackages::new(repository_dir)?;
+    packages
+        .packages_by_name
+        .get(name)
+        .map(|package| repository_dir.join(&package.root))
+        .ok_or_else(|| Error::PackageNotFound(name.to_string()))
+}
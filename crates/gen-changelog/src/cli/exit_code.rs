@@ -0,0 +1,40 @@
+//! Exit codes returned by the command line tool
+//!
+//! | Code | Meaning                                                 |
+//! | ---- | ------------------------------------------------------- |
+//! | 0    | Success                                                 |
+//! | 1    | Any other error                                         |
+//! | 2    | Invalid command line arguments                          |
+//! | 3    | Configuration file not found                            |
+//! | 4    | Configuration file is invalid                           |
+//! | 5    | Not a git repository                                    |
+//! | 6    | Changelog is out of date (`generate --check`)           |
+//! | 7    | No unreleased changes (`generate --require-unreleased`) |
+//! | 8    | Commit messages failed linting                          |
+//!
+//! Code 2 is returned by clap when parsing the arguments fails.
+
+use gen_changelog::Error;
+
+pub(crate) const FAILURE: i32 = 1;
+pub(crate) const CONFIG_NOT_FOUND: i32 = 3;
+pub(crate) const INVALID_CONFIG: i32 = 4;
+pub(crate) const NOT_A_REPOSITORY: i32 = 5;
+pub(crate) const CHANGELOG_OUT_OF_DATE: i32 = 6;
+pub(crate) const NO_UNRELEASED_CHANGES: i32 = 7;
+pub(crate) const LINT_FAILED: i32 = 8;
+
+/// Exit code reporting the error
+pub(crate) fn from_error(error: &Error) -> i32 {
+    match error {
+        Error::ConfigNotFound(_) => CONFIG_NOT_FOUND,
+        Error::TomlDeError(_) | Error::InvalidParserPattern(..) | Error::ConfigInvalid(_) => {
+            INVALID_CONFIG
+        }
+        Error::RepositoryNotFound(..) => NOT_A_REPOSITORY,
+        Error::ChangeLogOutOfDate(_) => CHANGELOG_OUT_OF_DATE,
+        Error::NoUnreleasedChanges => NO_UNRELEASED_CHANGES,
+        Error::LintFailed(_) => LINT_FAILED,
+        _ => FAILURE,
+    }
+}
@@ -1,11 +1,14 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::Parser;
 use gen_changelog::{
-    ChangeLog, ChangeLogConfig, DEFAULT_CHANGELOG_FILENAME, Error, ReleasePattern, RustPackages,
+    ChangeLog, ChangeLogConfig, CherryPickPolicy, DEFAULT_CHANGELOG_FILENAME, DiffCache, Error,
+    ReleasePattern, RustPackages,
 };
 use git2::Repository;
 
+use crate::config_cli::load_config;
+
 #[derive(Parser, Debug)]
 pub(crate) struct GenerateCli {
     /// The next version number for unreleased changes
@@ -38,12 +41,34 @@ pub(crate) struct GenerateCli {
     /// print the changelog to standard output
     #[arg(short, long)]
     show: bool,
+    /// check the saved changelog is up to date instead of saving it
+    #[arg(long)]
+    check: bool,
+    /// exit with an error when there are no unreleased changes
+    #[arg(long)]
+    require_unreleased: bool,
     /// name for changelog file
     #[arg(long, default_value = DEFAULT_CHANGELOG_FILENAME)]
     name: String,
     /// include merge commits in the changelog
     #[arg(long)]
     include_merge_commits: bool,
+    /// walk only the first-parent history, listing merges instead of the
+    /// commits they merged
+    #[arg(long)]
+    first_parent: bool,
+    /// number of threads classifying commits, 0 for one per CPU
+    #[arg(short, long, default_value_t = 1)]
+    jobs: usize,
+    /// generate the changelog for this branch and the releases made from it
+    #[arg(short, long)]
+    branch: Option<String>,
+    /// generate a single section for the commits after this revision
+    #[arg(long)]
+    from: Option<String>,
+    /// generate a single section for the commits up to this revision
+    #[arg(long)]
+    to: Option<String>,
 }
 
 impl GenerateCli {
@@ -52,7 +77,7 @@ impl GenerateCli {
         let repo_dir = PathBuf::new().join(&self.repository_dir);
         log::debug!("{}", repo_dir.display());
         let repository = Repository::open(&repo_dir)
-            .unwrap_or_else(|_| panic!("unable to open the repository at {}", repo_dir.display()));
+            .map_err(|e| Error::RepositoryNotFound(repo_dir.clone(), e))?;
 
         // Anchor output to an absolute repository path so the changelog is
         // written to the right place regardless of the working directory the
@@ -60,19 +85,21 @@ impl GenerateCli {
         // #284. Fall back to the given path if it cannot be canonicalised.
         let repo_root = repo_dir.canonicalize().unwrap_or_else(|_| repo_dir.clone());
 
-        let rust_package = if self.package.is_some() {
+        let rust_package = if let Some(p) = &self.package {
             let packages = RustPackages::new(&repo_dir)?;
             log::debug!("{packages:?}");
-            if let Some(p) = &self.package {
-                packages.packages_by_name.get(p).cloned()
-            } else {
-                None
-            }
+            let package = packages
+                .packages_by_name
+                .get(p)
+                .cloned()
+                .ok_or_else(|| Error::PackageNotFound(p.clone()))?;
+            Some(package)
         } else {
             None
         };
 
-        let mut config = self.make_config()?;
+        let package_root = rust_package.as_ref().map(|p| repo_dir.join(&p.root));
+        let mut config = self.make_config(&repo_dir, package_root.as_deref())?;
 
         // When targeting a specific package, treat only that package's
         // `<package>-v*` tags as release boundaries so the workspace shadow
@@ -81,41 +108,68 @@ impl GenerateCli {
             config.set_release_pattern(ReleasePattern::PackagePrefix("v".to_string()));
         }
 
+        // Keep the paths changed by each commit and its patch-id between runs,
+        // as filtering the commits for a package or finding cherry-picked
+        // changes diffs every commit
+        let diff_cache =
+            if self.package.is_some() || config.cherry_pick_policy() != CherryPickPolicy::All {
+                DiffCache::open(&repository)?
+            } else {
+                DiffCache::new()
+            };
+
         let mut change_log_builder = ChangeLog::builder();
         let change_log = change_log_builder
             .with_config(config)
             .with_summary_flag(self.display_summaries)
             .with_rust_package(rust_package)
             .with_package_name(self.package.clone())
+            .with_diff_cache(diff_cache.clone())
+            .with_jobs(self.jobs)
             .with_repository_root(Some(repo_root))
-            .walk_repository(&repository)
-            .unwrap()
+            .with_branch(self.branch.clone())
+            .with_from(self.from.clone())
+            .with_to(self.to.clone())
+            .walk_repository(&repository)?
             .update_unreleased_to_next_version(self.next_version.as_ref())
             .build();
 
-        if !self.no_save {
-            // Propagate save failures instead of silently exiting 0 (issue #284).
-            change_log.save(&self.name)?;
+        // The cache only speeds up later runs, so failing to save it must not
+        // stop the changelog being generated
+        if let Err(e) = diff_cache.save() {
+            log::warn!("failed to save the diff cache: {e}");
         }
+
         if self.show {
             println!("{change_log}");
         }
+        if self.require_unreleased && change_log.unreleased_changes() == 0 {
+            return Err(Error::NoUnreleasedChanges);
+        }
+        if self.check {
+            change_log.check(&self.name)?;
+        } else if !self.no_save {
+            // Propagate save failures instead of silently exiting 0 (issue #284).
+            change_log.save(&self.name)?;
+        }
         Ok(())
     }
 
-    fn make_config(&self) -> Result<ChangeLogConfig, gen_changelog::Error> {
-        let mut config = if let Some(cfg) = &self.config_file {
-            ChangeLogConfig::from_file(cfg)?
-        } else {
-            ChangeLogConfig::from_file_or_default()?
-        };
+    fn make_config(
+        &self,
+        repo_dir: &Path,
+        package_root: Option<&Path>,
+    ) -> Result<ChangeLogConfig, gen_changelog::Error> {
+        let mut config = load_config(self.config_file.as_deref(), repo_dir, package_root)?;
         log::debug!("initial config to build on: {config:?}");
 
-        config.publish_group("Security");
         config.set_display_sections(self.releases);
         config.add_commit_groups(&self.add_groups);
         config.remove_commit_groups(&self.remove_groups);
         config.set_include_merge_commits(self.include_merge_commits);
+        if self.first_parent {
+            config.set_first_parent(true);
+        }
 
         log::debug!("{config:#?}");
         Ok(config)
@@ -0,0 +1,82 @@
+use std::path::Path;
+
+use clap::{Parser, Subcommand};
+use gen_changelog::{Error, GitHook, HookInstaller, HookStatus};
+use git2::Repository;
+
+use crate::config_cli::load_config;
+
+#[derive(Parser, Debug)]
+pub(crate) struct HooksCli {
+    #[command(subcommand)]
+    command: HooksCommands,
+}
+
+#[derive(Subcommand, Debug)]
+enum HooksCommands {
+    /// Install git hooks that lint commit messages
+    #[clap(name = "install")]
+    Install(InstallArgs),
+}
+
+#[derive(Parser, Debug)]
+struct InstallArgs {
+    /// Also install a prepare-commit-msg hook adding a commit type template
+    #[arg(short, long)]
+    prepare_commit_msg: bool,
+    /// Path to the configuration file
+    #[arg(short, long)]
+    config_file: Option<String>,
+    /// Replace an existing `<hook>.local` when chaining an existing hook
+    #[arg(long)]
+    force: bool,
+    /// Path to the repository
+    #[arg(long, default_value = ".")]
+    repository_dir: String,
+}
+
+impl HooksCli {
+    pub(crate) fn run(&self) -> Result<(), Error> {
+        match &self.command {
+            HooksCommands::Install(args) => args.run(),
+        }
+    }
+}
+
+impl InstallArgs {
+    fn run(&self) -> Result<(), Error> {
+        log::debug!("Arguments to apply: {self:#?}");
+        let config = load_config(
+            self.config_file.as_deref(),
+            Path::new(&self.repository_dir),
+            None,
+        )?;
+        let repository = Repository::open(&self.repository_dir)
+            .map_err(|e| Error::RepositoryNotFound(self.repository_dir.clone().into(), e))?;
+        let mut installer = HookInstaller::new(&repository)?;
+        installer.set_force(self.force);
+
+        let mut hooks = vec![GitHook::CommitMsg];
+        if self.prepare_commit_msg {
+            hooks.push(GitHook::PrepareCommitMsg);
+        }
+
+        for hook in hooks {
+            match installer.install(hook, &config)? {
+                HookStatus::Installed(path) => {
+                    println!("Installed {} hook at {}", hook.name(), path.display())
+                }
+                HookStatus::Updated(path) => {
+                    println!("Updated {} hook at {}", hook.name(), path.display())
+                }
+                HookStatus::Chained(path, chained) => println!(
+                    "Installed {} hook at {}, running the existing hook moved to {}",
+                    hook.name(),
+                    path.display(),
+                    chained.display()
+                ),
+            }
+        }
+        Ok(())
+    }
+}
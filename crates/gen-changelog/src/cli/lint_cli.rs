@@ -0,0 +1,94 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, ValueEnum};
+use gen_changelog::{Error, LintReport, Linter};
+use git2::Repository;
+
+use crate::config_cli::load_config;
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+enum OutputFormat {
+    /// Human readable report
+    #[default]
+    Text,
+    /// JSON array of reports, one per commit
+    Json,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct LintCli {
+    /// Revision range to lint, e.g. `main..HEAD` [default: `@{upstream}..HEAD`
+    /// or `<latest tag>..HEAD`]
+    #[arg(conflicts_with = "message_file")]
+    range: Option<String>,
+    /// Lint the commit message in the file, as passed to a commit-msg hook
+    #[arg(short, long)]
+    message_file: Option<PathBuf>,
+    /// Path to the configuration file
+    #[arg(short, long)]
+    config_file: Option<String>,
+    /// Path to the repository
+    #[arg(long, default_value = ".")]
+    repository_dir: String,
+    /// Format of the report
+    #[arg(short, long, value_enum, default_value_t)]
+    format: OutputFormat,
+}
+
+impl LintCli {
+    pub(crate) fn run(&self) -> Result<(), Error> {
+        log::debug!("Arguments to apply: {self:#?}");
+        let config = load_config(
+            self.config_file.as_deref(),
+            Path::new(&self.repository_dir),
+            None,
+        )?;
+        let linter = Linter::new(&config)?;
+
+        let reports = if let Some(file) = &self.message_file {
+            let message = std::fs::read_to_string(file)?;
+            vec![linter.lint_message(&message)]
+        } else {
+            let repository = Repository::open(&self.repository_dir)
+                .map_err(|e| Error::RepositoryNotFound(self.repository_dir.clone().into(), e))?;
+            let range = match &self.range {
+                Some(range) => range.clone(),
+                None => Linter::default_range(&repository)?,
+            };
+            log::debug!("linting range `{range}`");
+            linter.lint_range(&repository, &range)?
+        };
+
+        match self.format {
+            OutputFormat::Text => print_text(&reports),
+            OutputFormat::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(&reports).expect("reports serialize to JSON")
+            ),
+        }
+
+        let failed = reports.iter().filter(|r| !r.is_valid()).count();
+        if failed > 0 {
+            Err(Error::LintFailed(failed))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn print_text(reports: &[LintReport]) {
+    for report in reports.iter().filter(|r| !r.is_valid()) {
+        let commit = report
+            .commit()
+            .map_or("message", |id| &id[..7.min(id.len())]);
+        println!("{commit} {}", report.subject());
+        for violation in report.violations() {
+            println!("  [{}] {}", violation.rule(), violation.message());
+        }
+    }
+    let failed = reports.iter().filter(|r| !r.is_valid()).count();
+    println!(
+        "{} commit message(s) checked, {failed} with violations",
+        reports.len()
+    );
+}
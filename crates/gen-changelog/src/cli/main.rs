@@ -1,11 +1,19 @@
 use std::error::Error;
 
+mod completions_cli;
 mod config_cli;
+mod exit_code;
 mod generate_cli;
+mod hooks_cli;
+mod lint_cli;
+mod man_cli;
 
 use clap::{Parser, Subcommand};
 
-use crate::{config_cli::ConfigCli, generate_cli::GenerateCli};
+use crate::{
+    completions_cli::CompletionsCli, config_cli::ConfigCli, generate_cli::GenerateCli,
+    hooks_cli::HooksCli, lint_cli::LintCli, man_cli::ManCli,
+};
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
@@ -24,6 +32,18 @@ enum Commands {
     /// Configuration management
     #[clap(name = "config")]
     Configuration(ConfigCli),
+    /// Lint commit messages
+    #[clap(name = "lint")]
+    Lint(LintCli),
+    /// Manage git hooks
+    #[clap(name = "hooks")]
+    Hooks(HooksCli),
+    /// Print a shell completion script
+    #[clap(name = "completions")]
+    Completions(CompletionsCli),
+    /// Generate man pages
+    #[clap(name = "man")]
+    Man(ManCli),
 }
 
 fn main() {
@@ -42,7 +62,7 @@ fn main() {
                 log::error!("{e}");
                 eprintln!("{e}");
             }
-            std::process::exit(101);
+            std::process::exit(exit_code::from_error(&e));
         }
     }
 }
@@ -52,6 +72,10 @@ fn run(args: Cli) -> Result<(), gen_changelog::Error> {
         match cmds {
             Commands::Generate(generate_cli) => generate_cli.run()?,
             Commands::Configuration(config_cli) => config_cli.run()?,
+            Commands::Lint(lint_cli) => lint_cli.run()?,
+            Commands::Hooks(hooks_cli) => hooks_cli.run()?,
+            Commands::Completions(completions_cli) => completions_cli.run()?,
+            Commands::Man(man_cli) => man_cli.run()?,
         }
     }
     Ok(())
@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+
+use clap::{CommandFactory, Parser};
+use clap_mangen::Man;
+use gen_changelog::Error;
+
+use crate::Cli;
+
+#[derive(Parser, Debug)]
+pub(crate) struct ManCli {
+    /// Write a page for the command and each subcommand to the directory
+    /// instead of printing the page for the command
+    #[arg(short, long)]
+    out_dir: Option<PathBuf>,
+}
+
+impl ManCli {
+    pub(crate) fn run(&self) -> Result<(), Error> {
+        log::debug!("Arguments to apply: {self:#?}");
+        let command = Cli::command();
+
+        if let Some(dir) = &self.out_dir {
+            std::fs::create_dir_all(dir)?;
+            clap_mangen::generate_to(command, dir)?;
+            println!("Man pages written to {}", dir.display());
+        } else {
+            Man::new(command).render(&mut std::io::stdout())?;
+        }
+        Ok(())
+    }
+}
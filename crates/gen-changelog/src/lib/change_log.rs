@@ -1,22 +1,25 @@
 mod header;
 mod link;
-mod section;
+pub(crate) mod section;
 mod tag;
 
 use std::{
-    cmp::min,
+    collections::{BTreeSet, HashMap, HashSet},
     fmt::{Debug, Display},
     path::PathBuf,
 };
 
-use git2::Repository;
+use git2::{Oid, Repository};
 use header::Header;
 use lazy_regex::{Lazy, Regex, lazy_regex};
 use link::Link;
-use section::{Section, WalkSetup};
+use section::{Section, WalkSetup, WalkedCommit, commit_parser::CommitParser};
 use tag::Tag;
 
-use crate::{ChangeLogConfig, Error, change_log_config::DisplaySections, package::RustPackage};
+use crate::{
+    ChangeLogConfig, CherryPickPolicy, DiffCache, Error, change_log_config::DisplaySections,
+    package::RustPackage,
+};
 
 /// default name for the file to save the changelog.
 pub const DEFAULT_CHANGELOG_FILENAME: &str = "CHANGELOG.md";
@@ -89,6 +92,61 @@ impl ChangeLog {
         ChangeLogBuilder::new()
     }
 
+    /// Returns the commit types, as written in the commit messages, that are
+    /// neither mapped to a group nor an alias of a mapped type.
+    ///
+    /// Commits of these types are listed under the unknown group, see
+    /// [`ChangeLogConfig::unknown_group`].
+    pub fn unmapped_types(&self) -> BTreeSet<String> {
+        unmapped_types(&self.sections)
+    }
+
+    /// Returns the number of published changes in the unreleased section.
+    ///
+    /// The unreleased section lists the commits after the latest release,
+    /// including when it has been given the next version. Returns zero when
+    /// every commit has been released.
+    pub fn unreleased_changes(&self) -> usize {
+        self.sections
+            .first()
+            .filter(|s| s.tag().is_none_or(|t| t.id().is_none()))
+            .map_or(0, Section::published_commits)
+    }
+
+    /// Checks that the saved changelog file matches the changelog.
+    ///
+    /// The file is found in the same place as [`ChangeLog::save`] writes it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ChangeLogOutOfDate`] if the file is missing or differs
+    /// from the changelog, or an error if the file cannot be read.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use gen_changelog::ChangeLog;
+    ///
+    /// let changelog = ChangeLog::builder().build();
+    /// changelog
+    ///     .check("CHANGELOG.md")
+    ///     .expect("changelog is up to date");
+    /// ```
+    pub fn check(&self, name: &str) -> Result<(), Error> {
+        let path = self.pkg_root.join(name);
+        log::debug!("checking changelog `{}`", path.display());
+        let saved = match std::fs::read_to_string(&path) {
+            Ok(saved) => saved,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        if saved == self.to_string() {
+            Ok(())
+        } else {
+            Err(Error::ChangeLogOutOfDate(path))
+        }
+    }
+
     /// Writes the changelog to a file named "CHANGELOG.md" in the current
     /// directory.
     ///
@@ -194,6 +252,16 @@ pub struct ChangeLogBuilder {
     /// path is anchored to this so `save` is independent of the current working
     /// directory (issue #284).
     repository_root: Option<PathBuf>,
+    /// Revision the single section of an explicit range starts after
+    from_revision: Option<String>,
+    /// Revision the single section of an explicit range ends at
+    to_revision: Option<String>,
+    /// Branch the changelog is generated for instead of HEAD
+    branch: Option<String>,
+    /// Paths changed by each commit, used to filter commits for a package
+    diff_cache: DiffCache,
+    /// Number of threads classifying commits
+    jobs: usize,
 }
 
 impl Debug for ChangeLogBuilder {
@@ -230,6 +298,11 @@ impl ChangeLogBuilder {
             config: ChangeLogConfig::default(),
             package_name: None,
             repository_root: None,
+            from_revision: None,
+            to_revision: None,
+            branch: None,
+            diff_cache: DiffCache::new(),
+            jobs: 1,
         }
     }
 
@@ -390,13 +463,132 @@ impl ChangeLogBuilder {
         self
     }
 
+    /// Sets the revision the changelog starts after.
+    ///
+    /// When a `from` or `to` revision is set the changelog has a single
+    /// section listing the commits reachable from the `to` revision and not
+    /// from the `from` revision, instead of a section per release. Without a
+    /// `from` revision the section runs to the first commit.
+    ///
+    /// # Arguments
+    ///
+    /// * `revision` - Any revision git can resolve to a commit, e.g. a tag,
+    ///   branch or commit id
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gen_changelog::ChangeLog;
+    ///
+    /// let builder = ChangeLog::builder()
+    ///     .with_from(Some("v1.0.0".to_string()))
+    ///     .with_to(Some("main".to_string()));
+    /// ```
+    pub fn with_from(&mut self, revision: Option<String>) -> &mut Self {
+        self.from_revision = revision;
+        self
+    }
+
+    /// Sets the revision the changelog ends at, the branch set by
+    /// [`with_branch`](Self::with_branch) or `HEAD` when not set.
+    ///
+    /// The section is headed with the release when the revision is a version
+    /// tag and as unreleased otherwise. See [`with_from`](Self::with_from).
+    ///
+    /// # Arguments
+    ///
+    /// * `revision` - Any revision git can resolve to a commit
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn with_to(&mut self, revision: Option<String>) -> &mut Self {
+        self.to_revision = revision;
+        self
+    }
+
+    /// Sets the branch the changelog is generated for, `HEAD` when not set.
+    ///
+    /// Only version tags on the history of the branch are used as release
+    /// boundaries, so a maintenance branch such as `release/1.x` gets the
+    /// releases made from it and not those made later on `main`.
+    ///
+    /// # Arguments
+    ///
+    /// * `branch` - The branch name, or any revision git can resolve to a
+    ///   commit
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gen_changelog::ChangeLog;
+    ///
+    /// let builder = ChangeLog::builder().with_branch(Some("release/1.x".to_string()));
+    /// ```
+    pub fn with_branch(&mut self, branch: Option<String>) -> &mut Self {
+        self.branch = branch;
+        self
+    }
+
+    /// Sets the cache of the paths changed by each commit, used to filter the
+    /// commits for a package.
+    ///
+    /// Pass clones of one cache to the builders of several packages to diff
+    /// each commit once, and use [`DiffCache::open`] to keep the cache between
+    /// runs. A cache held in memory only is used when not set.
+    pub fn with_diff_cache(&mut self, diff_cache: DiffCache) -> &mut Self {
+        self.diff_cache = diff_cache;
+        self
+    }
+
+    /// Sets the number of threads classifying the commits found by the walk.
+    ///
+    /// Classifying a commit parses its message and, for a package, diffs it,
+    /// so large histories are classified faster on several threads. The
+    /// changelog is the same whatever the number of threads. One thread is
+    /// used by default; zero uses one thread per available CPU.
+    ///
+    /// # Arguments
+    ///
+    /// * `jobs` - The number of threads
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gen_changelog::ChangeLog;
+    ///
+    /// let builder = ChangeLog::builder().with_jobs(4);
+    /// ```
+    pub fn with_jobs(&mut self, jobs: usize) -> &mut Self {
+        self.jobs = match jobs {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        log::debug!("Classifying commits on {} threads", self.jobs);
+        self
+    }
+
     /// Analyses a Git repository to populate changelog sections and links.
     ///
     /// This method performs the core changelog generation logic:
     /// 1. Extracts remote repository details (owner/repo)
     /// 2. Identifies and sorts version tags
     /// 3. Creates sections for each version
-    /// 4. Generates comparison links between versions
+    /// 4. Walks the history once in topological order, adding each commit to
+    ///    the section of the oldest release whose tag reaches it
+    /// 5. Generates comparison links between versions
     ///
     /// # Arguments
     ///
@@ -443,90 +635,132 @@ impl ChangeLogBuilder {
 
         let version_tags = self.get_version_tags(repository)?;
 
-        let section_limit = match self.config.display_sections() {
-            DisplaySections::All => min((version_tags.len() + 1) as u8, u8::MAX),
-            DisplaySections::One => 1,
-            DisplaySections::Custom(n) => min((version_tags.len() + 1) as u8, *n),
+        let parser = CommitParser::new(&self.config)?;
+        let new_section = |tag: Option<Tag>| {
+            Section::new(
+                tag,
+                &self.config,
+                &parser,
+                self.summary_flag,
+                self.include_merge_commits,
+            )
         };
 
-        let mut revwalk = repository.revwalk()?;
-        revwalk.set_sorting(git2::Sort::TIME)?;
-        let groups_mapping = self.config.groups_mapping();
-
-        let mut current_section = Section::new(
-            None,
-            self.config.headings(),
-            self.summary_flag,
-            &groups_mapping,
-            self.include_merge_commits,
-        );
-
-        // Case where no release has been made - no version tags
-        if version_tags.is_empty() {
-            let setup = WalkSetup::NoReleases;
-            current_section.walk_repository(
-                &setup,
-                repository,
-                &mut revwalk,
-                &self.rust_package,
-            )?;
-            self.sections.push(current_section);
-            self.set_link(&setup);
+        let mut walk = HistoryWalk::new(self.tip(repository)?);
+        let mut sections = Vec::new();
+        let mut setups = Vec::new();
+
+        // Case where an explicit revision range has been requested
+        if self.from_revision.is_some() || self.to_revision.is_some() {
+            // Any revision naming the commit of a release heads the section
+            // with it, e.g. `refs/tags/v1.0.0` or the commit id
+            let to_tag = match &self.to_revision {
+                Some(revision) => {
+                    let to = repository.revparse_single(revision)?.peel_to_commit()?.id();
+                    version_tags
+                        .iter()
+                        .find(|tag| tag_commit(repository, tag) == Some(to))
+                }
+                None => None,
+            };
+            sections.push(new_section(to_tag.cloned()));
+            setups.push(WalkSetup::RevisionRange);
+
+            let hide = match &self.from_revision {
+                Some(revision) => {
+                    Some(repository.revparse_single(revision)?.peel_to_commit()?.id())
+                }
+                None => None,
+            };
+            self.walk_history(repository, &mut walk, hide, &HashMap::new())?;
         } else {
+            let tag_commits = version_tags
+                .iter()
+                .map(|tag| tag_commit(repository, tag))
+                .collect::<Vec<_>>();
+            // Commits of release tags, mapped to the rank of the newest release
+            // on them, counting from 1 for the latest release
+            let mut boundaries = HashMap::new();
+            let mut release_commits = Vec::new();
+            for (rank, id) in tag_commits.iter().enumerate() {
+                if let Some(id) = id {
+                    boundaries.entry(*id).or_insert_with(|| {
+                        release_commits.push(*id);
+                        rank + 1
+                    });
+                }
+            }
+            // Number of releases listed after the unreleased changes
+            let limit = match self.config.display_sections() {
+                DisplaySections::All => None,
+                DisplaySections::One => Some(0),
+                DisplaySections::Custom(n) => Some(usize::from(n.saturating_sub(1))),
+            };
+            self.walk_releases(repository, &mut walk, &release_commits, &boundaries, limit)?;
+
+            // Releases in the history of the tip, newest first
+            let releases = version_tags
+                .iter()
+                .zip(&tag_commits)
+                .enumerate()
+                .filter_map(|(rank, (tag, id))| {
+                    let in_history = id.is_some_and(|id| walk.reaches(id));
+                    if !in_history {
+                        log::debug!(
+                            "Ignoring `{}` as it is not in the history of the tip",
+                            tag.name()
+                        );
+                    }
+                    in_history.then_some((rank + 1, tag))
+                })
+                .collect::<Vec<_>>();
+
             // get the unreleased
-            let setup = WalkSetup::HeadToRelease(version_tags.first().unwrap());
-            current_section.walk_repository(
-                &setup,
-                repository,
-                &mut revwalk,
-                &self.rust_package,
-            )?;
-            self.sections.push(current_section);
-            self.set_link(&setup);
+            sections.push(new_section(None));
+            match releases.first() {
+                Some((_, latest_tag)) => setups.push(WalkSetup::HeadToRelease(latest_tag)),
+                None => setups.push(WalkSetup::NoReleases),
+            }
 
             // get the releases
-            let mut section_count = 1;
-            let mut peekable_tags = version_tags.iter().peekable();
-            loop {
-                if section_count >= section_limit {
-                    break;
+            let listed = limit.map_or(releases.len(), |limit| limit.min(releases.len()));
+            let mut section_of_rank = HashMap::from([(0, 0)]);
+            for (index, (rank, tag)) in releases.iter().take(listed).enumerate() {
+                section_of_rank.insert(*rank, sections.len());
+                sections.push(new_section(Some((*tag).clone())));
+                match releases.get(index + 1) {
+                    Some((_, next_tag)) => {
+                        setups.push(WalkSetup::FromReleaseToRelease(tag, next_tag))
+                    }
+                    None => setups.push(WalkSetup::ReleaseToStart(tag)),
                 }
-                let Some(tag) = peekable_tags.next() else {
-                    break;
-                };
+            }
 
-                let mut section = Section::new(
-                    Some(tag.clone()),
-                    self.config.headings(),
-                    self.summary_flag,
-                    &groups_mapping,
-                    self.include_merge_commits,
-                );
+            // commits of releases that are not listed are dropped
+            walk.walked
+                .retain_mut(|(_, rank)| match section_of_rank.get(rank) {
+                    Some(index) => {
+                        *rank = *index;
+                        true
+                    }
+                    None => false,
+                });
+        }
 
-                let next_tag = peekable_tags.peek();
-
-                if let Some(next_tag) = next_tag {
-                    let setup = WalkSetup::FromReleaseToRelease(tag, next_tag);
-                    section.walk_repository(
-                        &setup,
-                        repository,
-                        &mut revwalk,
-                        &self.rust_package,
-                    )?;
-                    self.set_link(&setup);
-                } else {
-                    let setup = WalkSetup::ReleaseToStart(tag);
-                    section.walk_repository(
-                        &setup,
-                        repository,
-                        &mut revwalk,
-                        &self.rust_package,
-                    )?;
-                    self.set_link(&setup);
-                }
-                self.sections.push(section);
-                section_count += 1;
-            }
+        self.sections = sections;
+        self.add_walked_commits(repository, &walk.walked)?;
+        for setup in &setups {
+            self.set_link(setup)?;
+        }
+
+        self.remove_duplicate_changes();
+
+        let unmapped_types = unmapped_types(&self.sections);
+        if !unmapped_types.is_empty() {
+            log::warn!(
+                "commit types not mapped to a group: {}",
+                unmapped_types.into_iter().collect::<Vec<_>>().join(", ")
+            );
         }
 
         Ok(self)
@@ -579,6 +813,230 @@ impl ChangeLogBuilder {
 }
 
 impl ChangeLogBuilder {
+    /// Walks the history back to the oldest listed release.
+    ///
+    /// The history before the release following the listed ones is hidden
+    /// from the walk. Whether that release, or a listed one, is in the history
+    /// of the tip is only known once the walk is done: when it is not, the
+    /// walk resumes from where it stopped, hiding the next release instead. A
+    /// release tagged on another branch so costs no more than walking on to
+    /// the next release, and the history is never walked twice.
+    ///
+    /// # Arguments
+    ///
+    /// * `repository` - The Git repository to walk
+    /// * `walk` - The walk, started from the tip
+    /// * `releases` - The commits of release tags, newest release first
+    /// * `boundaries` - The commits of release tags and their rank
+    /// * `limit` - The number of releases listed, `None` for all of them
+    fn walk_releases(
+        &self,
+        repository: &Repository,
+        walk: &mut HistoryWalk,
+        releases: &[Oid],
+        boundaries: &HashMap<Oid, usize>,
+        limit: Option<usize>,
+    ) -> Result<(), Error> {
+        let mut off_history = HashSet::new();
+        loop {
+            let candidates = releases
+                .iter()
+                .filter(|id| !off_history.contains(*id))
+                .copied()
+                .collect::<Vec<_>>();
+            let hide = limit.and_then(|limit| candidates.get(limit).copied());
+            self.walk_history(repository, walk, hide, boundaries)?;
+
+            let (Some(hide), Some(limit)) = (hide, limit) else {
+                return Ok(());
+            };
+            if walk.pending.contains_key(&hide) {
+                // The hidden release is in the history, so listed releases
+                // that were not reached are not
+                let missed = candidates[..limit]
+                    .iter()
+                    .filter(|id| !walk.reached.contains(*id))
+                    .copied()
+                    .collect::<Vec<_>>();
+                if missed.is_empty() {
+                    return Ok(());
+                }
+                log::debug!("Releases not in the history of the tip: {missed:?}");
+                off_history.extend(missed);
+            } else {
+                log::debug!("Release `{hide}` is not in the history of the tip");
+                off_history.insert(hide);
+            }
+        }
+    }
+
+    /// Walks the history from the commits pending in the walk back to the
+    /// hidden commit or the first commit, giving each commit the rank of its
+    /// release.
+    ///
+    /// Commits are visited in topological order so that every commit is seen
+    /// after all of its children. Each commit takes the highest rank of its
+    /// children and of the release tags on it, which is the rank of the oldest
+    /// release that contains it, or 0 when no release contains it. A release
+    /// tag only counts when the walk reaches its commit, so tags that are not
+    /// in the history of the tip are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `repository` - The Git repository to walk
+    /// * `walk` - The walk to continue
+    /// * `hide` - The commit whose history is not walked
+    /// * `boundaries` - The commits of release tags and their rank
+    fn walk_history(
+        &self,
+        repository: &Repository,
+        walk: &mut HistoryWalk,
+        hide: Option<Oid>,
+        boundaries: &HashMap<Oid, usize>,
+    ) -> Result<(), Error> {
+        let first_parent = self.config.first_parent();
+        let mut revwalk = repository.revwalk()?;
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL)?;
+        if first_parent {
+            revwalk.simplify_first_parent()?;
+        }
+        for id in walk.pending.keys() {
+            revwalk.push(*id)?;
+        }
+        if let Some(hide) = hide {
+            revwalk.hide(hide)?;
+        }
+        log::trace!(
+            "Walking from {} commits back to `{hide:?}`",
+            walk.pending.len()
+        );
+
+        for oid in revwalk {
+            let oid = oid?;
+            let mut rank = walk.pending.remove(&oid).unwrap_or_default();
+            if let Some(release) = boundaries.get(&oid) {
+                walk.reached.insert(oid);
+                rank = rank.max(*release);
+            }
+            let commit = repository.find_commit(oid)?;
+
+            let parents = commit
+                .parent_ids()
+                .take(if first_parent { 1 } else { usize::MAX });
+            for parent in parents {
+                let parent_rank = walk.pending.entry(parent).or_insert(rank);
+                *parent_rank = (*parent_rank).max(rank);
+            }
+
+            walk.walked.push((oid, rank));
+        }
+
+        Ok(())
+    }
+
+    /// Classifies the walked commits and adds each to its section.
+    fn add_walked_commits(
+        &mut self,
+        repository: &Repository,
+        walked: &[(Oid, usize)],
+    ) -> Result<(), Error> {
+        let classified = if self.jobs > 1 && walked.len() > 1 {
+            self.classify_in_parallel(repository, walked)?
+        } else {
+            self.classify(repository, walked)?
+        };
+        // Commits are added in walk order whichever way they were classified
+        for ((_, index), walked_commit) in walked.iter().zip(classified) {
+            if let Some(walked_commit) = walked_commit {
+                self.sections[*index].add_walked_commit(walked_commit);
+            }
+        }
+
+        for section in self.sections.iter_mut() {
+            section.finish_walk();
+        }
+
+        Ok(())
+    }
+
+    /// Classifies the walked commits, in order, on the current thread.
+    fn classify(
+        &self,
+        repository: &Repository,
+        walked: &[(Oid, usize)],
+    ) -> Result<Vec<Option<WalkedCommit>>, Error> {
+        // Every section classifies commits the same way
+        let Some(classifier) = self.sections.first() else {
+            return Ok(Vec::new());
+        };
+        walked
+            .iter()
+            .map(|(oid, _)| {
+                let commit = repository.find_commit(*oid)?;
+                classifier.classify_commit(
+                    &commit,
+                    repository,
+                    &self.rust_package,
+                    &self.diff_cache,
+                )
+            })
+            .collect()
+    }
+
+    /// Classifies the walked commits on a pool of threads, each with its own
+    /// handle on the repository, returning them in walk order.
+    fn classify_in_parallel(
+        &self,
+        repository: &Repository,
+        walked: &[(Oid, usize)],
+    ) -> Result<Vec<Option<WalkedCommit>>, Error> {
+        let path = repository.path();
+        let chunk_size = walked.len().div_ceil(self.jobs);
+        log::debug!(
+            "Classifying {} commits on {} threads",
+            walked.len(),
+            walked.len().div_ceil(chunk_size)
+        );
+
+        std::thread::scope(|scope| {
+            let workers = walked
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        let repository = Repository::open(path)?;
+                        self.classify(&repository, chunk)
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let mut classified = Vec::with_capacity(walked.len());
+            for worker in workers {
+                let chunk = worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))?;
+                classified.extend(chunk);
+            }
+            Ok(classified)
+        })
+    }
+
+    /// Revision the walk starts from: the end of an explicit range, the
+    /// branch or `HEAD`.
+    fn tip_revision(&self) -> &str {
+        self.to_revision
+            .as_deref()
+            .or(self.branch.as_deref())
+            .unwrap_or("HEAD")
+    }
+
+    /// Resolves the revision the walk starts from to a commit.
+    fn tip(&self, repository: &Repository) -> Result<Oid, Error> {
+        Ok(repository
+            .revparse_single(self.tip_revision())?
+            .peel_to_commit()?
+            .id())
+    }
+
     /// Extracts GitHub repository owner and name from the remote origin URL.
     ///
     /// This method reads the Git configuration to find the remote origin URL
@@ -631,55 +1089,148 @@ impl ChangeLogBuilder {
     /// - Unreleased: Links to commits on main branch
     /// - Version comparisons: Links to GitHub compare view
     /// - Initial version: Links to release tag
+    /// - Revision range: Links to GitHub compare view of the revisions
     ///
     /// # Arguments
     ///
     /// * `setup` - The walk setup configuration determining link type
-    fn set_link(&mut self, setup: &WalkSetup) {
+    ///
+    /// # Errors
+    ///
+    /// - `Error::BadTag` - A release tag has no version
+    /// - `Error::InvalidLinkUrl` - The link is not a valid url
+    fn set_link(&mut self, setup: &WalkSetup) -> Result<(), Error> {
         match setup {
             WalkSetup::NoReleases => {
                 let url = format!(
-                    "https://github.com/{}/{}/commits/main/",
-                    self.owner, self.repo
+                    "https://github.com/{}/{}/commits/{}/",
+                    self.owner,
+                    self.repo,
+                    self.branch.as_deref().unwrap_or("main")
                 );
 
-                let link = Link::new("Unreleased", &url).unwrap();
-                self.links.push(link)
+                let link = Link::new("Unreleased", &url)?;
+                self.links.push(link);
             }
 
             WalkSetup::HeadToRelease(tag) => {
-                let tag_version = tag.version().unwrap().to_string();
+                let tag_version = tag.version_string()?;
                 let url = format!(
-                    "https://github.com/{}/{}/compare/v{}...HEAD",
-                    self.owner, self.repo, tag_version
+                    "https://github.com/{}/{}/compare/v{}...{}",
+                    self.owner,
+                    self.repo,
+                    tag_version,
+                    self.branch.as_deref().unwrap_or("HEAD")
                 );
-                let link = Link::new("Unreleased", &url).unwrap();
+                let link = Link::new("Unreleased", &url)?;
                 log::debug!("Head to release link: {link}");
-                self.links.push(link)
+                self.links.push(link);
             }
 
             WalkSetup::FromReleaseToRelease(tag, next_tag) => {
-                let tag_version = tag.version().unwrap().to_string();
-                let next_tag_version = next_tag.version().unwrap().to_string();
+                let tag_version = tag.version_string()?;
+                let next_tag_version = next_tag.version_string()?;
                 let url = format!(
                     "https://github.com/{}/{}/compare/v{}...v{}",
                     self.owner, self.repo, next_tag_version, tag_version
                 );
 
-                let link = Link::new(&tag_version, &url).unwrap();
-                self.links.push(link)
+                let link = Link::new(&tag_version, &url)?;
+                self.links.push(link);
             }
             WalkSetup::ReleaseToStart(tag) => {
-                let tag_version = tag.version().unwrap().to_string();
+                let tag_version = tag.version_string()?;
                 let url = format!(
                     "https://github.com/{}/{}/releases/tag/v{}",
                     self.owner, self.repo, tag_version
                 );
 
-                let link = Link::new(&tag_version, &url).unwrap();
-                self.links.push(link)
+                let link = Link::new(&tag_version, &url)?;
+                self.links.push(link);
+            }
+            WalkSetup::RevisionRange => {
+                let tag = self.sections.last().and_then(|s| s.tag());
+                let anchor = tag
+                    .and_then(|t| t.version())
+                    .map_or("Unreleased".to_string(), |v| v.to_string());
+                // Link to the release tag however the end of the range was given
+                let to = tag.map_or(self.tip_revision(), |t| {
+                    t.name().trim_start_matches("refs/tags/")
+                });
+                let url = match &self.from_revision {
+                    Some(from) => format!(
+                        "https://github.com/{}/{}/compare/{from}...{to}",
+                        self.owner, self.repo
+                    ),
+                    None => format!(
+                        "https://github.com/{}/{}/commits/{to}/",
+                        self.owner, self.repo
+                    ),
+                };
+
+                let link = Link::new(&anchor, &url)?;
+                self.links.push(link);
             }
         }
+
+        Ok(())
+    }
+
+    /// Lists each change once when it appears in more than one section, e.g.
+    /// a fix cherry-picked onto a maintenance branch.
+    ///
+    /// The sections are visited in the order of the configured
+    /// [`CherryPickPolicy`] and a commit is dropped if a commit already listed
+    /// shares its id, its patch-id or the commit it was cherry-picked from.
+    ///
+    /// A commit reverted in the sections is not compared, as the same change
+    /// landed again after the revert is not a copy of it.
+    fn remove_duplicate_changes(&mut self) {
+        let policy = self.config.cherry_pick_policy();
+        let order: Vec<usize> = match policy {
+            CherryPickPolicy::All => return,
+            // sections are held newest first
+            CherryPickPolicy::Earliest => (0..self.sections.len()).rev().collect(),
+            CherryPickPolicy::Latest => (0..self.sections.len()).collect(),
+        };
+
+        let ids: BTreeSet<&str> = self
+            .sections
+            .iter()
+            .flat_map(Section::commits)
+            .filter_map(|c| c.id())
+            .collect();
+        // The reverted hash may be abbreviated, so take the first id it prefixes
+        let reverted: HashSet<String> = self
+            .sections
+            .iter()
+            .flat_map(Section::commits)
+            .filter_map(|c| {
+                let hash = c.reverts()?;
+                let id = ids.range(hash..).next().filter(|id| id.starts_with(hash))?;
+                Some(id.to_string())
+            })
+            .collect();
+
+        let mut listed = HashSet::new();
+        for index in order {
+            self.sections[index].retain_commits(|commit| {
+                if commit.id().is_some_and(|id| reverted.contains(id)) {
+                    return true;
+                }
+                let keys = commit.change_keys();
+                if keys.iter().any(|k| listed.contains(k)) {
+                    log::debug!(
+                        "Dropping duplicate change `{}` under {policy:?} policy",
+                        commit.title_as_string()
+                    );
+                    false
+                } else {
+                    listed.extend(keys);
+                    true
+                }
+            });
+        }
     }
 
     /// Retrieves and processes version tags from the Git repository.
@@ -714,11 +1265,8 @@ impl ChangeLogBuilder {
             log::trace!(
                 "Identified `{}` as version `{:?}`",
                 tag.name(),
-                if tag.is_version_tag() {
-                    tag.version().unwrap().to_string()
-                } else {
-                    "NOT A VERSION".to_string()
-                }
+                tag.version()
+                    .map_or("NOT A VERSION".to_string(), |v| v.to_string())
             );
             tags.push(tag);
             true
@@ -726,7 +1274,7 @@ impl ChangeLogBuilder {
 
         let mut version_tags = tags.clone();
         version_tags.retain(|t| t.is_version_tag());
-        version_tags.sort_by_key(|k| k.version().unwrap().clone());
+        version_tags.sort_by_key(|k| k.version().cloned());
         version_tags.reverse();
         log::trace!("Identified {} version tags.", version_tags.len());
         log::trace!(
@@ -741,10 +1289,51 @@ impl ChangeLogBuilder {
     }
 }
 
+/// State of a walk of the history, which can be resumed further back
+#[derive(Debug, Default)]
+struct HistoryWalk {
+    /// Commits to visit, with the highest rank of their visited children
+    pending: HashMap<Oid, usize>,
+    /// Commits visited, in topological order, with their rank
+    walked: Vec<(Oid, usize)>,
+    /// Commits of release tags visited
+    reached: HashSet<Oid>,
+}
+
+impl HistoryWalk {
+    fn new(tip: Oid) -> Self {
+        HistoryWalk {
+            pending: HashMap::from([(tip, 0)]),
+            ..Default::default()
+        }
+    }
+
+    /// Whether the commit is in the history walked so far or is the next
+    /// to visit
+    fn reaches(&self, id: Oid) -> bool {
+        self.reached.contains(&id) || self.pending.contains_key(&id)
+    }
+}
+
+/// The commit a tag points to, peeling annotated tags
+fn tag_commit(repository: &Repository, tag: &Tag) -> Option<Oid> {
+    let object = repository.find_object(*tag.id()?, None).ok()?;
+    Some(object.peel_to_commit().ok()?.id())
+}
+
+/// Commit types not mapped to a group across the sections
+fn unmapped_types(sections: &[Section]) -> BTreeSet<String> {
+    sections
+        .iter()
+        .flat_map(|s| s.unmapped_types().iter().cloned())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
 
+    use git2::Oid;
     use tempfile::TempDir;
 
     use super::*;
@@ -1270,6 +1859,730 @@ mod tests {
         (td, repo)
     }
 
+    /// Builds a temp git repo with a GitHub `origin` remote and one commit per
+    /// `(author, message)` pair, oldest first, on a linear history.
+    fn fixture_repo_with_commits(commits: &[(&str, &str)]) -> (TempDir, Repository) {
+        let td = setup_temp_dir();
+        let repo = Repository::init(td.path()).expect("init repo");
+        repo.remote("origin", "https://github.com/jerus-org/fixture.git")
+            .expect("add remote");
+        {
+            let tree_id = repo.index().unwrap().write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            let mut parent: Option<git2::Commit> = None;
+            for (author, message) in commits {
+                let email = format!("{}@example.com", author.to_lowercase().replace(' ', "."));
+                let sig = git2::Signature::now(author, &email).expect("signature");
+                let parents: Vec<&git2::Commit> = parent.iter().collect();
+                let id = repo
+                    .commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+                    .unwrap();
+                parent = Some(repo.find_commit(id).unwrap());
+            }
+        }
+
+        (td, repo)
+    }
+
+    #[test]
+    fn test_walk_repository_drops_excluded_authors() {
+        let (_td, repo) = fixture_repo_with_commits(&[
+            ("Jane Doe", "feat: add search"),
+            ("renovate[bot]", "fix(deps): update rust crate foo to 1.2"),
+            ("Jane Doe", "fix: crash on empty input"),
+        ]);
+
+        let mut config = ChangeLogConfig::default();
+        let mut filter = crate::AuthorFilter::default();
+        filter.add_exclude("*[bot]");
+        config.set_author_filter(filter);
+
+        let changelog = ChangeLog::builder()
+            .with_config(config)
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .build();
+        let output = changelog.to_string();
+
+        assert!(output.contains("add search"));
+        assert!(output.contains("crash on empty input"));
+        assert!(!output.contains("update rust crate foo"));
+    }
+
+    #[test]
+    fn test_walk_repository_reports_unmapped_types() {
+        let (_td, repo) = fixture_repo_with_commits(&[
+            ("Jane Doe", "Feature: add search"),
+            ("Jane Doe", "wip: experiment"),
+            ("Jane Doe", "Release: prepare"),
+            ("Jane Doe", "Update readme"),
+        ]);
+        let mut config = ChangeLogConfig::default();
+        config.add_type_alias("feature", "feat");
+
+        let changelog = ChangeLog::builder()
+            .with_config(config)
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .build();
+
+        assert!(changelog.to_string().contains(" - Feature: add search\n"));
+        assert_eq!(
+            changelog.unmapped_types().into_iter().collect::<Vec<_>>(),
+            ["Release", "wip"]
+        );
+    }
+
+    #[test]
+    fn test_walk_repository_routes_excluded_authors_to_group() {
+        let (_td, repo) = fixture_repo_with_commits(&[
+            ("Jane Doe", "feat: add search"),
+            ("renovate[bot]", "fix(deps): update rust crate foo to 1.2"),
+        ]);
+
+        let mut config = ChangeLogConfig::default();
+        let mut filter = crate::AuthorFilter::default();
+        filter.add_exclude("*[bot]").set_group(Some("Dependencies"));
+        config.set_author_filter(filter);
+        config.publish_group("Dependencies");
+
+        let changelog = ChangeLog::builder()
+            .with_config(config)
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .build();
+        let output = changelog.to_string();
+
+        let fixed = output.find("### Fixed");
+        let dependencies = output
+            .find("### Dependencies")
+            .expect("dependencies heading");
+        assert!(fixed.is_none(), "bot commit should not be listed as a fix");
+        assert!(output[dependencies..].contains("update rust crate foo"));
+    }
+
+    /// Builds a temp git repo where `fix: crash` is made on `main` and
+    /// cherry-picked onto a maintenance branch released as `v0.1.1` and merged
+    /// back into `main`:
+    ///
+    /// ```text
+    /// v0.1.0 -- fix: crash -- merge -- feat: more (v0.2.0, main, HEAD)
+    ///      \                 /
+    ///       fix: crash [cherry-picked] (v0.1.1, release/0.1)
+    /// ```
+    fn fixture_repo_with_cherry_pick() -> (TempDir, Repository) {
+        let td = setup_temp_dir();
+        let repo = Repository::init(td.path()).expect("init repo");
+        repo.remote("origin", "https://github.com/jerus-org/fixture.git")
+            .expect("add remote");
+        {
+            let initial = fixture_commit(&repo, &[], &[("a.txt", "a")], "feat: initial");
+            fixture_tag(&repo, initial, "v0.1.0");
+            let fix = fixture_commit(
+                &repo,
+                &[initial],
+                &[("a.txt", "a"), ("b.txt", "b")],
+                "fix: crash",
+            );
+            let picked = fixture_commit(
+                &repo,
+                &[initial],
+                &[("a.txt", "a"), ("b.txt", "b")],
+                &format!("fix: crash\n\n(cherry picked from commit {fix})"),
+            );
+            fixture_tag(&repo, picked, "v0.1.1");
+            let merge = fixture_commit(
+                &repo,
+                &[fix, picked],
+                &[("a.txt", "a"), ("b.txt", "b")],
+                "Merge branch 'release/0.1'",
+            );
+            let more = fixture_commit(
+                &repo,
+                &[merge],
+                &[("a.txt", "a"), ("b.txt", "b"), ("c.txt", "c")],
+                "feat: more",
+            );
+            fixture_tag(&repo, more, "v0.2.0");
+
+            repo.reference("refs/heads/release/0.1", picked, true, "release")
+                .unwrap();
+            repo.reference("refs/heads/main", more, true, "main")
+                .unwrap();
+            repo.set_head("refs/heads/main").unwrap();
+        }
+
+        (td, repo)
+    }
+
+    /// Commits a tree holding `files` with the given parents.
+    fn fixture_commit(
+        repo: &Repository,
+        parents: &[Oid],
+        files: &[(&str, &str)],
+        message: &str,
+    ) -> Oid {
+        let sig = git2::Signature::now("Jane Doe", "jane@example.com").unwrap();
+        let mut builder = repo.treebuilder(None).unwrap();
+        for (name, content) in files {
+            let blob = repo.blob(content.as_bytes()).unwrap();
+            builder.insert(name, blob, 0o100644).unwrap();
+        }
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let parents: Vec<git2::Commit> = parents
+            .iter()
+            .map(|p| repo.find_commit(*p).unwrap())
+            .collect();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(None, &sig, &sig, message, &tree, &parents)
+            .unwrap()
+    }
+
+    fn fixture_tag(repo: &Repository, id: Oid, name: &str) {
+        let object = repo.find_object(id, None).unwrap();
+        repo.tag_lightweight(name, &object, false).unwrap();
+    }
+
+    /// Returns the markdown of the section for `version` in the changelog, or
+    /// an empty string when the section is not written.
+    fn section_for<'a>(changelog: &'a str, version: &str) -> &'a str {
+        let Some(start) = changelog.find(&format!("## [{version}]")) else {
+            return "";
+        };
+        let rest = &changelog[start + 1..];
+        let end = rest.find("## [").map_or(changelog.len(), |e| start + 1 + e);
+        &changelog[start..end]
+    }
+
+    #[rstest::rstest]
+    #[case(CherryPickPolicy::Earliest, false, true)]
+    #[case(CherryPickPolicy::Latest, true, false)]
+    #[case(CherryPickPolicy::All, true, true)]
+    fn test_cherry_pick_policy(
+        #[case] policy: CherryPickPolicy,
+        #[case] in_latest: bool,
+        #[case] in_maintenance: bool,
+    ) {
+        let (_td, repo) = fixture_repo_with_cherry_pick();
+
+        let mut config = ChangeLogConfig::default();
+        config.set_cherry_pick_policy(policy);
+
+        let changelog = ChangeLog::builder()
+            .with_config(config)
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .build()
+            .to_string();
+
+        assert!(section_for(&changelog, "0.2.0").contains("feat: more"));
+        assert_eq!(
+            section_for(&changelog, "0.2.0").contains("fix: crash"),
+            in_latest
+        );
+        assert_eq!(
+            section_for(&changelog, "0.1.1").contains("fix: crash"),
+            in_maintenance
+        );
+    }
+
+    #[rstest::rstest]
+    #[case(CherryPickPolicy::Earliest)]
+    #[case(CherryPickPolicy::Latest)]
+    fn test_cherry_pick_policy_keeps_change_landed_again_after_revert(
+        #[case] policy: CherryPickPolicy,
+    ) {
+        let td = setup_temp_dir();
+        let repo = Repository::init(td.path()).expect("init repo");
+        repo.remote("origin", "https://github.com/jerus-org/fixture.git")
+            .expect("add remote");
+        let initial = fixture_commit(&repo, &[], &[("a.txt", "a")], "feat: initial");
+        let search = fixture_commit(
+            &repo,
+            &[initial],
+            &[("a.txt", "a"), ("s.txt", "s")],
+            "feat: add search",
+        );
+        fixture_tag(&repo, search, "v1.0.0");
+        let revert = fixture_commit(
+            &repo,
+            &[search],
+            &[("a.txt", "a")],
+            &format!("Revert \"feat: add search\"\n\nThis reverts commit {search}."),
+        );
+        fixture_tag(&repo, revert, "v1.1.0");
+        let relanded = fixture_commit(
+            &repo,
+            &[revert],
+            &[("a.txt", "a"), ("s.txt", "s")],
+            "feat: add search",
+        );
+        fixture_tag(&repo, relanded, "v1.2.0");
+        repo.reference("refs/heads/main", relanded, true, "main")
+            .unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+
+        let mut config = ChangeLogConfig::default();
+        config.set_cherry_pick_policy(policy);
+        let changelog = ChangeLog::builder()
+            .with_config(config)
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .build()
+            .to_string();
+
+        assert!(section_for(&changelog, "1.0.0").contains("feat: add search"));
+        assert!(section_for(&changelog, "1.1.0").contains("### Reverted"));
+        assert!(section_for(&changelog, "1.2.0").contains("feat: add search"));
+    }
+
+    #[test]
+    fn test_cherry_pick_policy_matches_abbreviated_origin() {
+        let td = setup_temp_dir();
+        let repo = Repository::init(td.path()).expect("init repo");
+        repo.remote("origin", "https://github.com/jerus-org/fixture.git")
+            .expect("add remote");
+        let initial = fixture_commit(&repo, &[], &[("a.txt", "a")], "feat: initial");
+        fixture_tag(&repo, initial, "v0.1.0");
+        let fix = fixture_commit(
+            &repo,
+            &[initial],
+            &[("a.txt", "a"), ("b.txt", "b")],
+            "fix: crash",
+        );
+        // Picked with a conflict resolved differently, so the patch-ids differ
+        let short = &fix.to_string()[..7];
+        let picked = fixture_commit(
+            &repo,
+            &[initial],
+            &[("a.txt", "a"), ("b.txt", "b2")],
+            &format!("fix: crash\n\n(cherry picked from commit {short})"),
+        );
+        fixture_tag(&repo, picked, "v0.1.1");
+        let more = fixture_commit(
+            &repo,
+            &[fix, picked],
+            &[("a.txt", "a"), ("b.txt", "b"), ("c.txt", "c")],
+            "feat: more",
+        );
+        fixture_tag(&repo, more, "v0.2.0");
+        repo.reference("refs/heads/main", more, true, "main")
+            .unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+
+        let mut config = ChangeLogConfig::default();
+        config.set_cherry_pick_policy(CherryPickPolicy::Earliest);
+        let changelog = ChangeLog::builder()
+            .with_config(config)
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .build()
+            .to_string();
+
+        assert!(section_for(&changelog, "0.1.1").contains("fix: crash"));
+        assert!(!section_for(&changelog, "0.2.0").contains("fix: crash"));
+    }
+
+    #[rstest::rstest]
+    #[case(Some("v0.1.0"), Some("v0.2.0"), "0.2.0", "compare/v0.1.0...v0.2.0")]
+    #[case(Some("v0.1.0"), None, "Unreleased", "compare/v0.1.0...HEAD")]
+    #[case(None, Some("v0.1.1"), "0.1.1", "commits/v0.1.1/")]
+    #[case(
+        Some("v0.1.0"),
+        Some("refs/tags/v0.2.0"),
+        "0.2.0",
+        "compare/v0.1.0...v0.2.0"
+    )]
+    fn test_walk_repository_revision_range(
+        #[case] from: Option<&str>,
+        #[case] to: Option<&str>,
+        #[case] heading: &str,
+        #[case] url: &str,
+    ) {
+        let (_td, repo) = fixture_repo_with_cherry_pick();
+
+        let mut config = ChangeLogConfig::default();
+        config.set_cherry_pick_policy(CherryPickPolicy::All);
+
+        let changelog = ChangeLog::builder()
+            .with_config(config)
+            .with_from(from.map(String::from))
+            .with_to(to.map(String::from))
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .build();
+
+        assert_eq!(changelog.sections.len(), 1);
+        let changelog = changelog.to_string();
+        let section = section_for(&changelog, heading);
+        assert!(section.contains("fix: crash"));
+        assert_eq!(section.contains("feat: initial"), from.is_none());
+        assert_eq!(section.contains("feat: more"), to != Some("v0.1.1"));
+        assert!(changelog.contains(&format!("https://github.com/jerus-org/fixture/{url}")));
+    }
+
+    #[test]
+    fn test_walk_repository_revision_range_to_commit_id() {
+        let (_td, repo) = fixture_repo_with_cherry_pick();
+        let release = repo.revparse_single("v0.2.0^{commit}").unwrap().id();
+
+        let changelog = ChangeLog::builder()
+            .with_from(Some("v0.1.0".to_string()))
+            .with_to(Some(release.to_string()))
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .build()
+            .to_string();
+
+        assert!(section_for(&changelog, "0.2.0").contains("feat: more"));
+        assert!(changelog.contains("https://github.com/jerus-org/fixture/compare/v0.1.0...v0.2.0"));
+    }
+
+    #[test]
+    fn test_walk_repository_uses_tags_on_branch_lineage() {
+        let (_td, repo) = fixture_repo_with_cherry_pick();
+        let picked = repo.revparse_single("release/0.1").unwrap().id();
+        let backport = fixture_commit(
+            &repo,
+            &[picked],
+            &[("a.txt", "a"), ("b.txt", "b"), ("d.txt", "d")],
+            "fix: backport",
+        );
+        fixture_tag(&repo, backport, "v0.1.2");
+        repo.reference("refs/heads/release/0.1", backport, true, "release")
+            .unwrap();
+
+        let main = ChangeLog::builder()
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .build()
+            .to_string();
+        assert!(section_for(&main, "0.1.2").is_empty());
+        assert!(!main.contains("fix: backport"));
+
+        let release = ChangeLog::builder()
+            .with_branch(Some("release/0.1".to_string()))
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .build()
+            .to_string();
+        assert!(section_for(&release, "0.2.0").is_empty());
+        assert!(section_for(&release, "0.1.2").contains("fix: backport"));
+        assert!(section_for(&release, "0.1.1").contains("fix: crash"));
+        assert!(release.contains("compare/v0.1.2...release/0.1"));
+    }
+
+    #[rstest::rstest]
+    #[case(None, 2, "0.2.0", "compare/v0.1.1...v0.2.0")]
+    #[case(Some("release/0.1"), 3, "0.1.1", "compare/v0.1.0...v0.1.1")]
+    fn test_walk_repository_limited_skips_tags_off_lineage(
+        #[case] branch: Option<&str>,
+        #[case] display_sections: u8,
+        #[case] oldest: &str,
+        #[case] url: &str,
+    ) {
+        let (_td, repo) = fixture_repo_with_cherry_pick();
+        let picked = repo.revparse_single("release/0.1").unwrap().id();
+        let backport = fixture_commit(
+            &repo,
+            &[picked],
+            &[("a.txt", "a"), ("b.txt", "b"), ("d.txt", "d")],
+            "fix: backport",
+        );
+        fixture_tag(&repo, backport, "v0.1.2");
+        repo.reference("refs/heads/release/0.1", backport, true, "release")
+            .unwrap();
+
+        let mut config = ChangeLogConfig::default();
+        config.set_display_sections(Some(display_sections));
+        config.set_cherry_pick_policy(CherryPickPolicy::All);
+        let changelog = ChangeLog::builder()
+            .with_config(config)
+            .with_branch(branch.map(String::from))
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .build();
+
+        assert_eq!(changelog.sections.len(), usize::from(display_sections));
+        let changelog = changelog.to_string();
+        assert!(section_for(&changelog, oldest).contains("fix: crash"));
+        assert!(changelog.contains(&format!("https://github.com/jerus-org/fixture/{url}")));
+        assert!(!changelog.contains("feat: initial"));
+    }
+
+    #[test]
+    fn test_walk_repository_first_parent() {
+        let td = setup_temp_dir();
+        let repo = Repository::init(td.path()).expect("init repo");
+        repo.remote("origin", "https://github.com/jerus-org/fixture.git")
+            .expect("add remote");
+        let initial = fixture_commit(&repo, &[], &[("a.txt", "a")], "feat: initial");
+        let wip = fixture_commit(&repo, &[initial], &[("b.txt", "b")], "wip");
+        let search = fixture_commit(&repo, &[wip], &[("b.txt", "bb")], "feat: add search");
+        let pull_request = fixture_commit(
+            &repo,
+            &[initial, search],
+            &[("a.txt", "a"), ("b.txt", "bb")],
+            "Merge pull request #3 from jerus-org/search\n\nfeat: search the index",
+        );
+        let typo = fixture_commit(&repo, &[initial], &[("c.txt", "c")], "tidy up");
+        let crash = fixture_commit(&repo, &[typo], &[("c.txt", "cc")], "fix: crash");
+        let branch = fixture_commit(
+            &repo,
+            &[pull_request, crash],
+            &[("a.txt", "a"), ("b.txt", "bb"), ("c.txt", "cc")],
+            "Merge branch 'crash'\n\nConflicts:\n\tc.txt",
+        );
+        repo.reference("refs/heads/main", branch, true, "main")
+            .unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+
+        let mut config = ChangeLogConfig::default();
+        config.set_first_parent(true);
+        let changelog = ChangeLog::builder()
+            .with_config(config)
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .build()
+            .to_string();
+
+        assert!(changelog.contains("search the index (#3)"));
+        assert!(changelog.contains("crash"));
+        assert!(!changelog.contains("add search"));
+        assert!(!changelog.contains("wip"));
+        assert!(!changelog.contains("tidy up"));
+        assert!(!changelog.contains("Merge"));
+        assert!(!changelog.contains("Conflicts"));
+    }
+
+    #[test]
+    fn test_walk_repository_merge_request_title() {
+        let td = setup_temp_dir();
+        let repo = Repository::init(td.path()).expect("init repo");
+        repo.remote("origin", "https://github.com/jerus-org/fixture.git")
+            .expect("add remote");
+        let initial = fixture_commit(&repo, &[], &[("a.txt", "a")], "feat: initial");
+        let search = fixture_commit(&repo, &[initial], &[("b.txt", "b")], "add search");
+        let merge = fixture_commit(
+            &repo,
+            &[initial, search],
+            &[("a.txt", "a"), ("b.txt", "b")],
+            "Merge branch 'search' into 'main'\n\nfeat: search the index\n\nSee merge request jerus-org/fixture!7",
+        );
+        repo.reference("refs/heads/main", merge, true, "main")
+            .unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+
+        let mut config = ChangeLogConfig::default();
+        config.set_first_parent(true);
+        let changelog = ChangeLog::builder()
+            .with_config(config)
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .build()
+            .to_string();
+
+        assert!(changelog.contains("search the index (!7)"), "{changelog}");
+        assert!(!changelog.contains("add search"));
+    }
+
+    #[rstest::rstest]
+    #[case(None)]
+    #[case(Some("1.1.0"))]
+    fn test_unreleased_changes(#[case] next_version: Option<&str>) {
+        let td = setup_temp_dir();
+        let repo = Repository::init(td.path()).expect("init repo");
+        repo.remote("origin", "https://github.com/jerus-org/fixture.git")
+            .expect("add remote");
+        let first = fixture_commit(&repo, &[], &[("a", "1")], "feat: first");
+        fixture_tag(&repo, first, "v1.0.0");
+        repo.reference("refs/heads/main", first, true, "main")
+            .unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        let next_version = next_version.map(String::from);
+
+        let changelog = ChangeLog::builder()
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .update_unreleased_to_next_version(next_version.as_ref())
+            .build();
+        assert_eq!(changelog.unreleased_changes(), 0);
+
+        let second = fixture_commit(&repo, &[first], &[("a", "2")], "fix: second");
+        let third = fixture_commit(&repo, &[second], &[("a", "3")], "feat: third");
+        repo.reference("refs/heads/main", third, true, "main")
+            .unwrap();
+
+        let changelog = ChangeLog::builder()
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .update_unreleased_to_next_version(next_version.as_ref())
+            .build();
+        assert_eq!(changelog.unreleased_changes(), 2);
+    }
+
+    #[test]
+    fn test_check_saved_changelog() {
+        let (td, repo) = fixture_repo_with_commits(&[("Jane Doe", "feat: add search")]);
+        let changelog = ChangeLog::builder()
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .build();
+        let path = td.path().join(DEFAULT_CHANGELOG_FILENAME);
+
+        assert!(matches!(
+            changelog.check(DEFAULT_CHANGELOG_FILENAME),
+            Err(Error::ChangeLogOutOfDate(p)) if p == path
+        ));
+        changelog.save(DEFAULT_CHANGELOG_FILENAME).expect("save");
+        changelog
+            .check(DEFAULT_CHANGELOG_FILENAME)
+            .expect("saved changelog is up to date");
+
+        fs::write(&path, "# Changelog\n").unwrap();
+        assert!(matches!(
+            changelog.check(DEFAULT_CHANGELOG_FILENAME),
+            Err(Error::ChangeLogOutOfDate(_))
+        ));
+    }
+
+    #[rstest::rstest]
+    #[case(None, 4)]
+    #[case(Some(2), 2)]
+    fn test_walk_repository_assigns_commits_by_reachability(
+        #[case] releases: Option<u8>,
+        #[case] sections: usize,
+    ) {
+        // v0.1.0 -- v0.2.0 -- v0.3.0 -- merge -- HEAD
+        //     \___ feat: late ___________/
+        let td = setup_temp_dir();
+        let repo = Repository::init(td.path()).expect("init repo");
+        repo.remote("origin", "https://github.com/jerus-org/fixture.git")
+            .expect("add remote");
+        let first = fixture_commit(&repo, &[], &[("a", "1")], "feat: first");
+        fixture_tag(&repo, first, "v0.1.0");
+        let late = fixture_commit(&repo, &[first], &[("b", "1")], "feat: late");
+        let second = fixture_commit(&repo, &[first], &[("a", "2")], "fix: second");
+        fixture_tag(&repo, second, "v0.2.0");
+        let third = fixture_commit(&repo, &[second], &[("a", "3")], "fix: third");
+        fixture_tag(&repo, third, "v0.3.0");
+        let merge = fixture_commit(&repo, &[third, late], &[("a", "3"), ("b", "1")], "Merge");
+        let head = fixture_commit(&repo, &[merge], &[("a", "4")], "fix: head");
+        repo.reference("refs/heads/main", head, true, "main")
+            .unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+
+        let mut config = ChangeLogConfig::default();
+        config.set_display_sections(releases);
+        let changelog = ChangeLog::builder()
+            .with_config(config)
+            .walk_repository(&repo)
+            .expect("walk repository")
+            .build();
+
+        assert_eq!(changelog.sections.len(), sections);
+        let changelog = changelog.to_string();
+        let unreleased = section_for(&changelog, "Unreleased");
+        assert!(unreleased.contains("head"));
+        assert!(unreleased.contains("late"));
+        assert!(section_for(&changelog, "0.3.0").contains("third"));
+        assert_eq!(changelog.contains("second"), releases.is_none());
+        assert_eq!(changelog.contains("first"), releases.is_none());
+    }
+
+    #[test]
+    fn test_walk_repository_caches_patch_ids() {
+        let (_td, repo) = fixture_repo_with_cherry_pick();
+        let mut config = ChangeLogConfig::default();
+        config.set_cherry_pick_policy(CherryPickPolicy::Earliest);
+
+        let diff_cache = DiffCache::open(&repo).expect("open diff cache");
+        let changelog_with = |diff_cache: DiffCache| {
+            ChangeLog::builder()
+                .with_config(config.clone())
+                .with_diff_cache(diff_cache)
+                .walk_repository(&repo)
+                .expect("walk repository")
+                .build()
+                .to_string()
+        };
+        let first = changelog_with(diff_cache.clone());
+        // The commits other than the merge have a patch-id
+        assert_eq!(diff_cache.len(), 4);
+        diff_cache.save().expect("save diff cache");
+
+        let diff_cache = DiffCache::open(&repo).expect("open diff cache");
+        assert_eq!(changelog_with(diff_cache.clone()), first);
+        assert_eq!(diff_cache.len(), 4);
+    }
+
+    #[test]
+    fn test_walk_repository_shares_diff_cache_across_packages() {
+        let td = setup_temp_dir();
+        let repo = Repository::init(td.path()).expect("init repo");
+        repo.remote("origin", "https://github.com/jerus-org/fixture.git")
+            .expect("add remote");
+        let first = fixture_commit(&repo, &[], &[("one.rs", "1")], "feat: add one");
+        let second = fixture_commit(
+            &repo,
+            &[first],
+            &[("one.rs", "1"), ("two.rs", "1")],
+            "feat: add two",
+        );
+        repo.reference("refs/heads/main", second, true, "main")
+            .unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+
+        let diff_cache = DiffCache::open(&repo).expect("open diff cache");
+        let changelog_for = |root: &str| {
+            ChangeLog::builder()
+                .with_rust_package(Some(RustPackage {
+                    root: root.to_string(),
+                    dependencies: Vec::new(),
+                }))
+                .with_diff_cache(diff_cache.clone())
+                .walk_repository(&repo)
+                .expect("walk repository")
+                .build()
+                .to_string()
+        };
+
+        let one = changelog_for("one");
+        assert_eq!(diff_cache.len(), 2);
+        let two = changelog_for("two");
+        assert_eq!(diff_cache.len(), 2);
+        assert!(one.contains("add one") && !one.contains("add two"));
+        assert!(two.contains("add two") && !two.contains("add one"));
+
+        diff_cache.save().expect("save diff cache");
+        assert_eq!(DiffCache::open(&repo).expect("open diff cache").len(), 2);
+    }
+
+    #[rstest::rstest]
+    #[case(None)]
+    #[case(Some("b"))]
+    fn test_walk_repository_parallel_matches_sequential(#[case] package: Option<&str>) {
+        let (_td, repo) = fixture_repo_with_cherry_pick();
+        let changelog_with = |jobs: usize| {
+            ChangeLog::builder()
+                .with_rust_package(package.map(|root| RustPackage {
+                    root: root.to_string(),
+                    dependencies: Vec::new(),
+                }))
+                .with_jobs(jobs)
+                .walk_repository(&repo)
+                .expect("walk repository")
+                .build()
+                .to_string()
+        };
+
+        let sequential = changelog_with(1);
+        assert!(sequential.contains("crash"));
+        for jobs in [0, 2, 3, 16] {
+            assert_eq!(changelog_with(jobs), sequential, "with {jobs} jobs");
+        }
+    }
+
     /// #274: with a package selected, only that package's `<pkg>-v*` tags are
     /// treated as release boundaries; the workspace `v*` shadow tag is ignored.
     #[test]
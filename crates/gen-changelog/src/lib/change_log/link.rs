@@ -1,14 +1,8 @@
 use std::fmt::Display;
 
-use thiserror::Error;
 use url::Url;
 
-#[derive(Debug, Error)]
-pub(crate) enum LinkError {
-    /// Parse error reported by Url crate
-    #[error("Url says parse failed because: {0}")]
-    UrlError(#[from] url::ParseError),
-}
+use crate::Error;
 
 #[derive(Debug, Clone)]
 pub(crate) struct Link {
@@ -32,9 +26,9 @@ impl Display for Link {
 }
 
 impl Link {
-    pub(crate) fn new(anchor: &str, url: &str) -> Result<Self, LinkError> {
+    pub(crate) fn new(anchor: &str, url: &str) -> Result<Self, Error> {
         let anchor = anchor.to_string();
-        let url = Url::parse(url)?;
+        let url = Url::parse(url).map_err(|e| Error::InvalidLinkUrl(url.to_string(), e))?;
 
         Ok(Link { anchor, url })
     }
@@ -1,25 +1,52 @@
-mod cc_commit;
+pub(crate) mod cc_commit;
+pub(crate) mod commit_parser;
 mod section_header;
 
-use std::{collections::BTreeMap, fmt::Display, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt::Display,
+    path::PathBuf,
+};
 
-use git2::{Commit, Repository, Revwalk};
+use git2::{Commit, Oid, Repository};
+use lazy_regex::{Lazy, Regex, lazy_regex};
 
 use crate::{
     change_log::{
         Error,
-        section::{cc_commit::ConvCommit, section_header::SectionHeader},
+        section::{
+            cc_commit::ConvCommit, commit_parser::CommitParser, section_header::SectionHeader,
+        },
         tag::Tag,
     },
-    change_log_config::heading_mgmt::HeadingMgmt,
+    change_log_config::{
+        AuthorFilter, ChangeLogConfig, CherryPickPolicy, RoutingRule, ScopeGroups, ScopeMode,
+        heading_mgmt::HeadingMgmt, normalise_type,
+    },
+    diff_cache::DiffCache,
     package::RustPackage,
 };
 
+/// Subject of a merge commit made by GitHub for a pull request
+static MERGE_PULL_REQUEST: Lazy<Regex> = lazy_regex!(r"^Merge pull request #(?P<number>\d+) from ");
+/// Trailing line of the body of a merge commit made by GitLab for a merge
+/// request
+static MERGE_REQUEST: Lazy<Regex> = lazy_regex!(r"(?m)^See merge request \S+!(?P<number>\d+)$");
+
 pub(crate) enum WalkSetup<'a> {
     NoReleases,
     HeadToRelease(&'a Tag),
     FromReleaseToRelease(&'a Tag, &'a Tag),
     ReleaseToStart(&'a Tag),
+    RevisionRange,
+}
+
+/// A commit found by the repository walk, classified for a section
+#[derive(Debug)]
+pub(crate) struct WalkedCommit {
+    commit: ConvCommit,
+    // group the commit is routed to by its author
+    group: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -29,7 +56,17 @@ pub(crate) struct Section {
     headings: BTreeMap<u8, String>,
     summary_flag: bool,
     include_merge_commits: bool,
+    first_parent: bool,
     groups_mapping: BTreeMap<String, String>,
+    type_aliases: BTreeMap<String, String>,
+    // commit types, as written, that are not mapped to a group
+    unmapped_types: BTreeSet<String>,
+    routing_rules: Vec<RoutingRule>,
+    unknown_group: String,
+    scope_groups: ScopeGroups,
+    author_filter: AuthorFilter,
+    cherry_pick_policy: CherryPickPolicy,
+    parser: CommitParser,
     // commits in the section by group
     commits: BTreeMap<String, Vec<ConvCommit>>,
 }
@@ -43,11 +80,12 @@ impl Display for Section {
 impl Section {
     pub(crate) fn new(
         tag: Option<Tag>,
-        headings: &BTreeMap<u8, String>,
+        config: &ChangeLogConfig,
+        parser: &CommitParser,
         summary_flag: bool,
-        group_mapping: &BTreeMap<String, String>,
         include_merge_commits: bool,
     ) -> Self {
+        let headings = config.headings();
         log::trace!("Section headings to publish: {headings:?}");
 
         let header = SectionHeader::from(&tag);
@@ -58,138 +96,313 @@ impl Section {
             headings: headings.to_owned(),
             summary_flag,
             include_merge_commits,
-            groups_mapping: group_mapping.to_owned(),
+            first_parent: config.first_parent(),
+            groups_mapping: config.groups_mapping(),
+            type_aliases: config.type_aliases().clone(),
+            unmapped_types: BTreeSet::new(),
+            routing_rules: config.routing_rules().to_vec(),
+            unknown_group: config.unknown_group().to_string(),
+            scope_groups: config.scope_groups().clone(),
+            author_filter: config.author_filter().clone(),
+            cherry_pick_policy: config.cherry_pick_policy(),
+            parser: parser.clone(),
             commits: Default::default(),
         }
     }
 
-    pub(crate) fn walk_repository(
-        &mut self,
-        setup: &WalkSetup,
+    /// Classifies a commit found by the repository walk, unless it is filtered
+    /// out as a merge, by its author or as unrelated to the package.
+    ///
+    /// Classification only reads the section configuration, so commits can be
+    /// classified on any thread and added to the section in walk order.
+    pub(crate) fn classify_commit(
+        &self,
+        commit: &Commit,
         repository: &Repository,
-        revwalk: &mut Revwalk,
         rust_package: &Option<RustPackage>,
-    ) -> Result<&mut Self, Error> {
-        match setup {
-            WalkSetup::NoReleases => {
-                revwalk.push_head()?;
-                log::trace!("Walking from the HEAD to the first commit");
-                self.get_commits(revwalk, repository, rust_package);
-                log::trace!("{}", self.report_status(false));
-            }
-            WalkSetup::HeadToRelease(tag) => {
-                revwalk.push_head()?;
-                let reference = tag.to_string();
-                revwalk.hide_ref(&reference)?;
-                log::trace!("Walking from the HEAD to the last release `{tag}`",);
-                self.get_commits(revwalk, repository, rust_package);
-                log::trace!("{}", self.report_status(false));
-            }
-            WalkSetup::FromReleaseToRelease(from_tag, to_tag) => {
-                revwalk.push(*from_tag.id().unwrap())?;
-                let reference = to_tag.to_string();
-                revwalk.hide_ref(&reference)?;
-                log::trace!("Walking from the release `{from_tag}` to release `{to_tag}`");
-                self.get_commits(revwalk, repository, rust_package);
-                log::trace!("{}", self.report_status(false));
-            }
-            WalkSetup::ReleaseToStart(tag) => {
-                revwalk.push(*tag.id().unwrap())?;
-                log::trace!("Walking from the first release `{tag}` to first commit");
-                self.get_commits(revwalk, repository, rust_package);
-                log::trace!("{}", self.report_status(false));
-            }
+        diff_cache: &DiffCache,
+    ) -> Result<Option<WalkedCommit>, Error> {
+        // Skip merge commits unless explicitly included or they stand for
+        // the merged changes on the first-parent history
+        let is_merge = commit.parent_count() > 1;
+        if !self.include_merge_commits && !self.first_parent && is_merge {
+            log::debug!(
+                "Skipping merge commit: {}",
+                commit
+                    .summary()
+                    .ok()
+                    .flatten()
+                    .unwrap_or("***no subject***")
+            );
+            return Ok(None);
         }
 
-        Ok(self)
-    }
+        // git2 0.21: Commit::summary() returns Result<Option<&str>, Error>.
+        let mut summary = commit.summary().ok().flatten();
+
+        // Filter out commits by excluded authors, e.g. bots and release
+        // automation, unless they are routed to a dedicated group
+        let author = commit.author();
+        let excluded_author = self.author_filter.is_excluded(
+            author.name().unwrap_or_default(),
+            author.email().unwrap_or_default(),
+        );
+        if excluded_author && self.author_filter.group().is_none() {
+            log::debug!(
+                "Skipping commit by excluded author `{}`: {}",
+                author.name().unwrap_or("***no name***"),
+                summary.unwrap_or("***no subject***")
+            );
+            return Ok(None);
+        }
 
-    fn get_commits(
-        &mut self,
-        revwalk: &mut Revwalk,
-        repository: &Repository,
-        rust_package: &Option<RustPackage>,
-    ) -> &mut Self {
-        for oid in revwalk.flatten() {
-            let Ok(commit) = repository.find_commit(oid) else {
-                continue;
-            };
+        // Filter out commits not in scope for a package
+        if let Some(rp) = rust_package {
+            // We are processing a rust package and will need to filter the
+            // commits based on
+            // - the root directory
+            // - update to a dependency of the package
 
-            // Skip merge commits unless explicitly included
-            if !self.include_merge_commits && commit.parent_count() > 1 {
+            let subject = summary.unwrap_or("***no subject***");
+            let files_in_commit =
+                self.list_files_related_to_commit(commit, repository, diff_cache)?;
+
+            if !rp.is_related_to_package(subject, files_in_commit) {
                 log::debug!(
-                    "Skipping merge commit: {}",
-                    commit
-                        .summary()
-                        .ok()
-                        .flatten()
-                        .unwrap_or("***no subject***")
+                    "commit {subject} not related to crate {:?}",
+                    rp.root.split('/').next_back()
                 );
-                continue;
+                return Ok(None);
             }
+        }
 
-            // git2 0.21: Commit::summary() returns Result<Option<&str>, Error>.
-            let summary = commit.summary().ok().flatten();
-
-            // Filter out commits not in scope for a package
-            if let Some(rp) = rust_package {
-                // We are processing a rust package and will need to filter the
-                // commits based on
-                // - the root directory
-                // - update to a dependency of the package
-
-                let subject = summary.unwrap_or("***no subject***");
-                let files_in_commit = self.list_files_related_to_commit(&commit, repository);
-
-                if !rp.is_related_to_package(subject, files_in_commit) {
-                    log::debug!(
-                        "commit {subject} not related to crate {:?}",
-                        rp.root.split('/').next_back()
-                    );
-                    continue;
+        // git2 0.21: Commit::body() returns Result<Option<&str>, Error>.
+        let mut body = commit.body().ok().flatten();
+        let merge_title = if self.first_parent && is_merge {
+            self.merge_title(commit, repository)
+        } else {
+            None
+        };
+        if merge_title.is_some() {
+            summary = merge_title.as_deref();
+            body = None;
+        }
+        let Some(s) = summary else {
+            return Ok(None);
+        };
+        log::trace!("Found commit with Summary:\t`{s}.");
+        let mut conventional_commit = ConvCommit::with_parser(summary, body, &self.parser);
+        conventional_commit.set_id(commit.id());
+        if self.cherry_pick_policy != CherryPickPolicy::All {
+            if let Some(patch_id) = self.patch_id(commit, repository, diff_cache) {
+                conventional_commit.set_patch_id(patch_id);
+            }
+            // Expand an abbreviated hash so that it matches the full commit id
+            if let Some(origin) = conventional_commit
+                .cherry_picked_from()
+                .filter(|hash| hash.len() < 40)
+            {
+                match repository.revparse_single(origin) {
+                    Ok(object) => {
+                        conventional_commit.set_cherry_picked_from(object.id());
+                    }
+                    Err(e) => log::debug!("cherry-picked commit `{origin}` not found: {e}"),
                 }
             }
+        }
+        let group = match (excluded_author, self.author_filter.group()) {
+            (true, Some(group)) => Some(group.to_string()),
+            _ => None,
+        };
 
-            // git2 0.21: Commit::body() returns Result<Option<&str>, Error>.
-            let body = commit.body().ok().flatten();
-            if summary.is_some() {
-                self.add_commit(summary, body);
-            }
-            if let Some(s) = summary {
-                log::trace!("Found commit with Summary:\t`{s}.");
-            }
+        Ok(Some(WalkedCommit {
+            commit: conventional_commit,
+            group,
+        }))
+    }
+
+    /// Adds a commit classified by [`classify_commit`](Self::classify_commit)
+    /// to the section.
+    pub(crate) fn add_walked_commit(&mut self, walked: WalkedCommit) -> &mut Self {
+        match walked.group {
+            Some(group) => self.add_commit_to_group(&group, walked.commit),
+            None => self.add_commit(walked.commit),
         }
+        self
+    }
 
+    /// Completes the section once the repository walk has added its commits.
+    pub(crate) fn finish_walk(&mut self) -> &mut Self {
+        self.cancel_reverts();
+        log::trace!("{}", self.report_status(false));
         self
     }
 
+    /// Title classifying a merge commit on the first-parent history.
+    ///
+    /// A merge made for a pull request (GitHub, recognised by its subject) or
+    /// merge request (GitLab, recognised by the `See merge request` line)
+    /// carries the request title on the first line of its body. Other merges
+    /// take the subject of the first conventional commit on the merged branch,
+    /// falling back to the merge subject.
+    fn merge_title(&self, commit: &Commit, repository: &Repository) -> Option<String> {
+        let summary = commit.summary().ok().flatten()?;
+        if !summary.starts_with("Merge ") {
+            return Some(summary.to_string());
+        }
+
+        // Only merges made by a forge carry the request title in the body;
+        // the body of other merges may list conflicts or anything else
+        let body = commit.body().ok().flatten().unwrap_or_default();
+        let number = MERGE_PULL_REQUEST
+            .captures(summary)
+            .map(|caps| format!("#{}", &caps["number"]))
+            .or_else(|| {
+                MERGE_REQUEST
+                    .captures(body)
+                    .map(|caps| format!("!{}", &caps["number"]))
+            });
+        let request_title = body.lines().map(str::trim).find(|l| !l.is_empty());
+        if let (Some(number), Some(title)) = (number, request_title) {
+            let title = format!("{title} ({number})");
+            log::debug!("Merge commit `{summary}` classified by its request title `{title}`");
+            return Some(title);
+        }
+
+        let title = self
+            .merged_commits(commit, repository)
+            .into_iter()
+            .find(|subject| {
+                ConvCommit::with_parser(Some(subject), None, &self.parser).is_conventional()
+            });
+        log::debug!("Merge commit `{summary}` summarised by the merged commit `{title:?}`");
+        title.or_else(|| Some(summary.to_string()))
+    }
+
+    /// Subjects of the commits merged by a merge commit, oldest first.
+    fn merged_commits(&self, commit: &Commit, repository: &Repository) -> Vec<String> {
+        let walk = || -> Result<Vec<String>, git2::Error> {
+            let mut revwalk = repository.revwalk()?;
+            revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+            for parent in commit.parent_ids().skip(1) {
+                revwalk.push(parent)?;
+            }
+            revwalk.hide(commit.parent_id(0)?)?;
+            Ok(revwalk
+                .flatten()
+                .filter_map(|oid| repository.find_commit(oid).ok())
+                .filter(|c| c.parent_count() == 1)
+                .filter_map(|c| c.summary().ok().flatten().map(str::to_string))
+                .collect())
+        };
+        walk().unwrap_or_else(|e| {
+            log::warn!(
+                "failed to walk the commits merged by `{}`: {e}",
+                commit.id()
+            );
+            Vec::new()
+        })
+    }
+
     pub(crate) fn list_files_related_to_commit(
         &self,
         commit: &Commit,
         repository: &Repository,
-    ) -> Vec<PathBuf> {
+        diff_cache: &DiffCache,
+    ) -> Result<Vec<PathBuf>, Error> {
+        // The paths changed by a merge depend on the parent compared with
+        let cacheable = commit.parent_count() < 2;
+        if cacheable {
+            if let Some(paths) = diff_cache.paths(commit.id()) {
+                return Ok(paths);
+            }
+        }
+
+        let diff_failed = |e| Error::DiffFailed(commit.id().to_string(), e);
         let mut diff_files = vec![];
 
-        let a = if commit.parents().len() == 1 {
-            let parent = commit.parent(0).unwrap();
-            Some(parent.tree().unwrap())
+        // On the first-parent history a merge commit is compared with the
+        // mainline it was merged into
+        let a = if commit.parents().len() == 1 || (self.first_parent && commit.parents().len() > 1)
+        {
+            let parent = commit.parent(0).map_err(diff_failed)?;
+            Some(parent.tree().map_err(diff_failed)?)
         } else {
             None
         };
-        let b = commit.tree().unwrap();
+        let b = commit.tree().map_err(diff_failed)?;
         let diff = repository
             .diff_tree_to_tree(a.as_ref(), Some(&b), None)
-            .unwrap();
+            .map_err(diff_failed)?;
         let ds = diff.deltas();
         for d in ds {
-            let file_name = d.new_file().path().unwrap().to_owned();
-            // .unwrap().to_str().unwrap().to_owned()
+            let Some(file_name) = d.new_file().path() else {
+                continue;
+            };
             if !file_name.starts_with("master") {
-                diff_files.push(file_name);
+                diff_files.push(file_name.to_owned());
             }
         }
 
-        diff_files
+        if cacheable {
+            diff_cache.insert_paths(commit.id(), diff_files.clone());
+        }
+        Ok(diff_files)
+    }
+
+    /// Compute the git patch-id of the changes made by a commit.
+    ///
+    /// Merge commits and commits without changes have no patch-id.
+    fn patch_id(
+        &self,
+        commit: &Commit,
+        repository: &Repository,
+        diff_cache: &DiffCache,
+    ) -> Option<Oid> {
+        if commit.parent_count() > 1 {
+            return None;
+        }
+        if let Some(patch_id) = diff_cache.patch_id(commit.id()) {
+            return Some(patch_id);
+        }
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree().ok()?),
+            Err(_) => None,
+        };
+        let tree = commit.tree().ok()?;
+        let diff = repository
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+            .ok()?;
+        if diff.deltas().len() == 0 {
+            return None;
+        }
+
+        match diff.patchid(None) {
+            Ok(patch_id) => {
+                diff_cache.insert_patch_id(commit.id(), patch_id);
+                Some(patch_id)
+            }
+            Err(e) => {
+                log::warn!("failed to compute the patch-id of `{}`: {e}", commit.id());
+                None
+            }
+        }
+    }
+
+    /// The commits listed in the section
+    pub(crate) fn commits(&self) -> impl Iterator<Item = &ConvCommit> {
+        self.commits.values().flatten()
+    }
+
+    /// Keep only the commits for which `keep` returns true
+    pub(crate) fn retain_commits<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&ConvCommit) -> bool,
+    {
+        for commits in self.commits.values_mut() {
+            commits.retain(&mut keep);
+        }
+        self.commits.retain(|_, commits| !commits.is_empty());
     }
 
     fn add_commit_to_hashmap(&mut self, class: &str, commit: ConvCommit) {
@@ -205,51 +418,118 @@ impl Section {
     }
 
     fn add_conventional_commit(&mut self, commit: &ConvCommit, kind: &str) {
-        let group = match kind.to_lowercase().as_str() {
-            "chore" => {
-                if let Some(s) = commit.scope() {
-                    if s.as_str() == "deps" {
-                        "Security".to_string()
-                    } else {
-                        "Chore".to_string()
-                    }
-                } else {
-                    "Chore".to_string()
-                }
-            }
-            _ => {
-                if let Some(g) = self.groups_mapping.get(kind) {
-                    g.clone()
-                } else {
-                    "Unknown".to_string()
-                }
-            }
+        let scope = commit.scope();
+        let normalised = normalise_type(&self.type_aliases, kind);
+        let group = if let Some(rule) = self
+            .routing_rules
+            .iter()
+            .find(|rule| rule.matches(&normalised, scope.as_deref()))
+        {
+            rule.group().to_string()
+        } else if let Some(g) = self.groups_mapping.get(&normalised) {
+            g.clone()
+        } else {
+            self.unmapped_types.insert(kind.to_string());
+            self.unknown_group.clone()
         };
 
         self.add_commit_to_hashmap(&group, commit.clone());
     }
 
     fn add_non_conventional_commit(&mut self, commit: &ConvCommit) {
-        let group = "Unknown".to_string();
+        let group = self.unknown_group.clone();
 
         self.add_commit_to_hashmap(&group, commit.clone());
     }
 
-    pub(crate) fn add_commit(&mut self, summary: Option<&str>, message: Option<&str>) {
-        let conventional_commit = ConvCommit::new(summary, message);
-        if let Some(k) = conventional_commit.kind() {
+    pub(crate) fn add_commit(&mut self, conventional_commit: ConvCommit) {
+        if conventional_commit.skip() {
+            log::debug!(
+                "Skipping commit marked `Changelog: skip`: {}",
+                conventional_commit.title_as_string()
+            );
+            return;
+        }
+        if let Some(g) = conventional_commit.group() {
+            let group = g.to_string();
+            self.add_commit_to_hashmap(&group, conventional_commit);
+        } else if let Some(k) = conventional_commit.kind() {
             self.add_conventional_commit(&conventional_commit, &k);
         } else {
             self.add_non_conventional_commit(&conventional_commit);
         }
     }
 
+    /// Add a commit to the given group unless its message directs otherwise
+    fn add_commit_to_group(&mut self, group: &str, conventional_commit: ConvCommit) {
+        if conventional_commit.skip() {
+            log::debug!(
+                "Skipping commit marked `Changelog: skip`: {}",
+                conventional_commit.title_as_string()
+            );
+            return;
+        }
+        let group = conventional_commit.group().unwrap_or(group).to_string();
+        self.add_commit_to_hashmap(&group, conventional_commit);
+    }
+
+    /// Drop reverts together with the commit they revert when both fall in
+    /// this section. Reverts of commits outside the section are kept and
+    /// listed under the group mapped to the `revert` type.
+    fn cancel_reverts(&mut self) {
+        let ids: BTreeSet<&str> = self
+            .commits
+            .values()
+            .flatten()
+            .filter_map(|c| c.id())
+            .collect();
+        // Each revert with the commit it reverts, when that is in this section.
+        // The reverted hash may be abbreviated, so take the first id it prefixes.
+        let targets: HashMap<&str, &str> = self
+            .commits
+            .values()
+            .flatten()
+            .filter_map(|c| {
+                let hash = c.reverts()?;
+                let target = ids.range(hash..).next().filter(|id| id.starts_with(hash))?;
+                Some((c.id()?, *target))
+            })
+            .collect();
+        let reverted: HashSet<&str> = targets.values().copied().collect();
+
+        // Follow each chain of reverts from a revert that is not itself
+        // reverted, cancelling it with its target. A revert reverted by the
+        // cancelled target is then no longer reverted, so a revert of a revert
+        // restores the original commit.
+        let mut cancelled = HashSet::new();
+        for head in targets.keys().filter(|id| !reverted.contains(*id)) {
+            let mut revert = *head;
+            while let Some(&target) = targets.get(revert) {
+                // Another revert of the same commit has cancelled it
+                if cancelled.contains(target) {
+                    break;
+                }
+                log::debug!("Revert `{revert}` cancels `{target}` in the same section");
+                cancelled.insert(revert.to_string());
+                cancelled.insert(target.to_string());
+                match targets.get(target) {
+                    Some(&next) => revert = next,
+                    None => break,
+                }
+            }
+        }
+
+        if !cancelled.is_empty() {
+            self.retain_commits(|c| !c.id().is_some_and(|id| cancelled.contains(id)));
+        }
+    }
+
     pub(crate) fn report_status(&self, summary: bool) -> String {
         if summary {
             let mut report = String::from("Summary: ");
             let mut comma_flag = false;
             for (h, c) in self.commits.iter() {
-                if h == "Unknown" {
+                if h == &self.unknown_group && !self.headings.contains(h) {
                     continue;
                 }
                 if comma_flag {
@@ -279,6 +559,21 @@ impl Section {
         }
     }
 
+    /// Number of commits listed under the published headings
+    pub(crate) fn published_commits(&self) -> usize {
+        self.headings
+            .values()
+            .filter_map(|h| self.commits.get(h))
+            .map(Vec::len)
+            .sum()
+    }
+
+    /// Commit types, as written in the commit messages, that are not mapped to
+    /// a group
+    pub(crate) fn unmapped_types(&self) -> &BTreeSet<String> {
+        &self.unmapped_types
+    }
+
     pub(crate) fn tag(&self) -> Option<&Tag> {
         self.tag.as_ref()
     }
@@ -369,13 +664,353 @@ impl Section {
         if !self.headings.contains(heading) | commits.is_empty() {
             None
         } else {
-            Some(format!(
-                "### {heading}\n\n{}\n",
-                commits
+            let list = match self.scope_groups.mode() {
+                ScopeMode::Off => commits
                     .iter()
                     .map(|c| format!(" - {}\n", c.title_as_string()))
-                    .collect::<String>()
-            ))
+                    .collect::<String>(),
+                mode => self.scoped_commits_markdown(mode, commits),
+            };
+            Some(format!("### {heading}\n\n{list}\n"))
+        }
+    }
+
+    /// List the commits sorted by scope, with a heading for each scope or
+    /// with the scope as a bold prefix.
+    fn scoped_commits_markdown(&self, mode: ScopeMode, commits: &[ConvCommit]) -> String {
+        let mut sorted = commits.iter().collect::<Vec<_>>();
+        sorted.sort_by_cached_key(|c| self.scope_groups.sort_key(c.scope().as_deref()));
+
+        let mut list = String::new();
+        let mut current_name = None;
+        for commit in sorted {
+            let name = commit.scope().map(|s| self.scope_groups.display_name(&s));
+            match (mode, &name) {
+                (ScopeMode::Headings, Some(name)) if current_name.as_ref() != Some(name) => {
+                    if !list.is_empty() {
+                        list.push('\n');
+                    }
+                    list.push_str(&format!("#### {name}\n\n"));
+                }
+                // The label replaces the `type(scope):` prefix of the title
+                (ScopeMode::Bold, Some(name)) => {
+                    list.push_str(&format!(
+                        " - **{name}**: {}\n",
+                        commit.description_as_string()
+                    ));
+                    continue;
+                }
+                _ => {}
+            }
+            list.push_str(&format!(" - {}\n", commit.title_as_string()));
+            current_name = name;
+        }
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_section() -> Section {
+        Section::new(
+            None,
+            &ChangeLogConfig::default(),
+            &CommitParser::default(),
+            false,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_add_commit_respects_skip_directive() {
+        let mut section = test_section();
+        section.add_commit(ConvCommit::new(Some("feat: add search"), None));
+        section.add_commit(ConvCommit::new(
+            Some("feat: internal tooling"),
+            Some("Changelog: skip"),
+        ));
+
+        let markdown = section.section_markdown();
+        assert!(markdown.contains("add search"));
+        assert!(!markdown.contains("internal tooling"));
+    }
+
+    #[test]
+    fn test_add_commit_respects_group_and_entry_directives() {
+        let mut section = test_section();
+        section.add_commit(ConvCommit::new(
+            Some("fix: handle tokens"),
+            Some("Changelog-Group: Security\nChangelog-Entry: Redact access tokens"),
+        ));
+
+        let markdown = section.section_markdown();
+        assert!(!markdown.contains("### Fixed"));
+        assert!(markdown.contains("### Security\n\n - fix: Redact access tokens\n"));
+    }
+
+    #[test]
+    fn test_add_commit_applies_routing_rules() {
+        let mut section = test_section();
+        section.add_commit(ConvCommit::new(Some("chore(deps): bump git2"), None));
+        section.add_commit(ConvCommit::new(Some("chore: tidy"), None));
+
+        let markdown = section.section_markdown();
+        assert!(markdown.contains("### Security\n\n - chore(deps): bump git2\n"));
+        assert!(!markdown.contains("tidy"));
+
+        let mut config = ChangeLogConfig::default();
+        config
+            .clear_routing_rules()
+            .add_routing_rule(RoutingRule::new("fix", Some("sec*"), "Security"));
+        let mut section = Section::new(None, &config, &CommitParser::default(), false, false);
+        section.add_commit(ConvCommit::new(Some("chore(deps): bump git2"), None));
+        section.add_commit(ConvCommit::new(Some("fix(secrets): redact tokens"), None));
+
+        let markdown = section.section_markdown();
+        assert!(!markdown.contains("bump git2"));
+        assert!(markdown.contains("### Security\n\n - fix(secrets): redact tokens\n"));
+    }
+
+    #[test]
+    fn test_unknown_group_published() {
+        let mut section = test_section();
+        section.add_commit(ConvCommit::new(Some("Update readme"), None));
+        assert!(!section.section_markdown().contains("Update readme"));
+        assert!(!section.report_status(true).contains("Unknown"));
+
+        let mut config = ChangeLogConfig::default();
+        config.publish_group("Unknown");
+        let mut section = Section::new(None, &config, &CommitParser::default(), false, false);
+        section.add_commit(ConvCommit::new(Some("Update readme"), None));
+        section.add_commit(ConvCommit::new(Some("wip: experiment"), None));
+
+        let markdown = section.section_markdown();
+        assert!(markdown.contains("### Unknown\n\n - Update readme\n - wip: experiment\n"));
+        assert!(section.report_status(true).contains("Unknown[2]"));
+    }
+
+    #[test]
+    fn test_types_are_case_insensitive_and_aliased() {
+        let mut config = ChangeLogConfig::default();
+        config
+            .add_type_alias("Feature", "feat")
+            .add_type_alias("perf", "refactor");
+        let mut section = Section::new(None, &config, &CommitParser::default(), false, false);
+        for summary in [
+            "FEAT: shout",
+            "Feature(cli): add flag",
+            "Fix: typo",
+            "perf: faster walk",
+            "WIP: experiment",
+            "wip: more",
+        ] {
+            section.add_commit(ConvCommit::new(Some(summary), None));
+        }
+
+        let markdown = section.section_markdown();
+        assert!(markdown.contains("### Added\n\n - FEAT: shout\n - Feature(cli): add flag\n"));
+        assert!(markdown.contains("### Fixed\n\n - Fix: typo\n"));
+        assert!(markdown.contains("### Changed\n\n - perf: faster walk\n"));
+        assert_eq!(
+            section.unmapped_types().iter().collect::<Vec<_>>(),
+            ["WIP", "wip"]
+        );
+    }
+
+    fn scoped_section(mode: ScopeMode) -> Section {
+        let mut scope_groups = ScopeGroups::default();
+        scope_groups
+            .set_mode(mode)
+            .set_order(&["core"])
+            .set_name("core", "Core library");
+        let mut config = ChangeLogConfig::default();
+        config.set_scope_groups(scope_groups);
+
+        let mut section = Section::new(None, &config, &CommitParser::default(), false, false);
+        for summary in [
+            "fix(cli): quote paths",
+            "fix: typo",
+            "fix(core): walk tags",
+            "fix(api): status code",
+            "fix(core): empty repo",
+        ] {
+            section.add_commit(ConvCommit::new(Some(summary), None));
+        }
+        section
+    }
+
+    #[test]
+    fn test_scope_groups_as_headings() {
+        let markdown = scoped_section(ScopeMode::Headings).section_markdown();
+
+        let expected = "### Fixed
+
+ - fix: typo
+
+#### Core library
+
+ - fix(core): walk tags
+ - fix(core): empty repo
+
+#### api
+
+ - fix(api): status code
+
+#### cli
+
+ - fix(cli): quote paths
+
+";
+        assert!(markdown.contains(expected), "{markdown}");
+    }
+
+    #[test]
+    fn test_scope_groups_as_bold_prefix() {
+        let markdown = scoped_section(ScopeMode::Bold).section_markdown();
+
+        let expected = "### Fixed
+
+ - fix: typo
+ - **Core library**: walk tags
+ - **Core library**: empty repo
+ - **api**: status code
+ - **cli**: quote paths
+
+";
+        assert!(markdown.contains(expected), "{markdown}");
+    }
+
+    #[test]
+    fn test_scopes_sharing_a_name_under_one_heading() {
+        let mut scope_groups = ScopeGroups::default();
+        scope_groups
+            .set_mode(ScopeMode::Headings)
+            .set_name("core", "Core library")
+            .set_name("lib", "Core library");
+        let mut config = ChangeLogConfig::default();
+        config.set_scope_groups(scope_groups);
+
+        let mut section = Section::new(None, &config, &CommitParser::default(), false, false);
+        for summary in [
+            "fix(core): walk tags",
+            "fix(api): status code",
+            "fix(lib): empty repo",
+            "fix(core): quote paths",
+        ] {
+            section.add_commit(ConvCommit::new(Some(summary), None));
+        }
+        let markdown = section.section_markdown();
+
+        let expected = "#### Core library
+
+ - fix(core): walk tags
+ - fix(lib): empty repo
+ - fix(core): quote paths
+
+";
+        assert!(markdown.contains(expected), "{markdown}");
+        assert_eq!(markdown.matches("#### Core library").count(), 1);
+    }
+
+    fn commit_with_id(id: &str, summary: &str, body: Option<&str>) -> ConvCommit {
+        let mut commit = ConvCommit::new(Some(summary), body);
+        commit.set_id(git2::Oid::from_str(id).unwrap());
+        commit
+    }
+
+    const FEAT_ID: &str = "1111111111111111111111111111111111111111";
+    const REVERT_ID: &str = "2222222222222222222222222222222222222222";
+    const REVERT_REVERT_ID: &str = "3333333333333333333333333333333333333333";
+
+    #[test]
+    fn test_revert_in_same_section_cancels_reverted_commit() {
+        let mut section = test_section();
+        section.add_commit(commit_with_id(
+            REVERT_ID,
+            "Revert \"feat: add search\"",
+            Some(&format!("This reverts commit {FEAT_ID}.")),
+        ));
+        section.add_commit(commit_with_id(FEAT_ID, "feat: add search", None));
+        section.add_commit(commit_with_id(
+            "4444444444444444444444444444444444444444",
+            "fix: crash",
+            None,
+        ));
+        section.cancel_reverts();
+
+        let markdown = section.section_markdown();
+        assert!(!markdown.contains("add search"));
+        assert!(!markdown.contains("### Reverted"));
+        assert!(markdown.contains("fix: crash"));
+    }
+
+    #[test]
+    fn test_revert_of_commit_in_earlier_section_is_listed() {
+        let mut section = test_section();
+        section.add_commit(commit_with_id(
+            REVERT_ID,
+            "revert: drop search",
+            Some("This reverts commit 1111111."),
+        ));
+        section.cancel_reverts();
+
+        let markdown = section.section_markdown();
+        assert!(markdown.contains("### Reverted\n\n - revert: drop search\n"));
+    }
+
+    #[test]
+    fn test_revert_of_revert_restores_original() {
+        let mut section = test_section();
+        section.add_commit(commit_with_id(
+            REVERT_REVERT_ID,
+            "Revert \"Revert \"feat: add search\"\"",
+            Some(&format!("This reverts commit {REVERT_ID}.")),
+        ));
+        section.add_commit(commit_with_id(
+            REVERT_ID,
+            "Revert \"feat: add search\"",
+            Some(&format!("This reverts commit {FEAT_ID}.")),
+        ));
+        section.add_commit(commit_with_id(FEAT_ID, "feat: add search", None));
+        section.cancel_reverts();
+
+        let markdown = section.section_markdown();
+        assert!(markdown.contains("### Added\n\n - feat: add search\n"));
+        assert!(!markdown.contains("### Reverted"));
+    }
+
+    #[test]
+    fn test_chain_of_reverts_cancels_in_pairs() {
+        let mut section = test_section();
+        for (id, reverts, summary) in [
+            (
+                "4444444",
+                Some(REVERT_REVERT_ID),
+                "revert: drop search again",
+            ),
+            (REVERT_REVERT_ID, Some(REVERT_ID), "revert: restore search"),
+            (REVERT_ID, Some("1111111"), "revert: drop search"),
+            (FEAT_ID, None, "feat: add search"),
+            ("5555555", None, "feat: add filters"),
+        ] {
+            let body = reverts.map(|r| format!("This reverts commit {r}."));
+            section.add_commit(commit_with_id(
+                &format!("{id:0<40}"),
+                summary,
+                body.as_deref(),
+            ));
         }
+        section.cancel_reverts();
+
+        // Both pairs cancel, leaving the unrelated feature
+        let markdown = section.section_markdown();
+        assert!(
+            markdown.contains("### Added\n\n - feat: add filters\n"),
+            "{markdown}"
+        );
+        assert!(!markdown.contains("search"), "{markdown}");
+        assert!(!markdown.contains("### Reverted"), "{markdown}");
     }
 }
@@ -7,69 +7,206 @@
 //! and body are displayed correctly regardless whether or not the commit is a
 //! conventional commit.
 //!
+//! Custom parser rules from the configuration are tried in order before the
+//! built-in conventional commit pattern (see [`CommitParser`]). When gitmoji
+//! classification is enabled, a summary that is not a conventional commit but
+//! starts with a known emoji or `:shortcode:` is given the group mapped to it.
+//!
 //! No analysis is done on the body to separate main information and footer
 //! information. This is stored and reported as it is in the original commit
 //! message.
+//!
+//! The body is scanned for changelog directives given as trailers:
+//! - `Changelog: skip` leaves the commit out of the changelog
+//! - `Changelog-Group: <group>` lists the commit under the named group
+//! - `Changelog-Entry: <text>` replaces the description in the changelog entry
+//!
+//! Reverts are recognised from git's standard `Revert "<summary>"` message as
+//! well as the `revert` conventional commit type. The reverted commit is taken
+//! from the `This reverts commit <hash>` line in the body.
+//!
+//! Cherry-picked commits are recognised by the patch-id of their changes and
+//! by the `(cherry picked from commit <hash>)` line added by `git cherry-pick
+//! -x`.
 
-use lazy_regex::{Lazy, Regex, lazy_regex};
+use git2::Oid;
+use lazy_regex::{Captures, Lazy, Regex, lazy_regex};
+
+use crate::change_log::section::commit_parser::CommitParser;
 
 pub static CONVENTIONAL: Lazy<Regex> = lazy_regex!(
-    r"^(?P<emoji>.+\s)?(?P<type>[a-z]+)(?:\((?P<scope>.+)\))?(?P<breaking>!)?: (?P<description>.*)$$"
+    r"^(?P<emoji>.+\s)?(?P<type>[a-zA-Z]+)(?:\((?P<scope>.+)\))?(?P<breaking>!)?: (?P<description>.*)$$"
 );
 
+/// Summary of a revert commit created by `git revert`.
+///
+/// Captures named groups:
+/// - `reverted`: The summary of the reverted commit
+pub static REVERT: Lazy<Regex> = lazy_regex!(r#"^Revert "(?P<reverted>.+)"$"#);
+
+/// Reference to the reverted commit in the body of a revert commit.
+///
+/// Captures named groups:
+/// - `hash`: The full or abbreviated hash of the reverted commit
+pub static REVERTED_COMMIT: Lazy<Regex> =
+    lazy_regex!(r"(?m)^This reverts commit (?P<hash>[0-9a-fA-F]{7,40})\b");
+
+/// Reference to the original commit in the body of a cherry-picked commit.
+///
+/// Captures named groups:
+/// - `hash`: The hash of the original commit
+pub static CHERRY_PICKED_FROM: Lazy<Regex> =
+    lazy_regex!(r"(?m)^\(cherry picked from commit (?P<hash>[0-9a-fA-F]{7,40})\)");
+
+/// Changelog directive trailers in the commit body.
+///
+/// Captures named groups:
+/// - `key`: `group` or `entry`; absent for the plain `Changelog:` trailer
+/// - `value`: The value of the trailer
+pub static DIRECTIVE: Lazy<Regex> =
+    lazy_regex!(r"(?mi)^changelog(?:-(?P<key>group|entry))?:[ \t]*(?P<value>\S.*?)[ \t]*$");
+
 #[derive(Debug, Default, Clone)]
 pub(crate) struct ConvCommit {
+    id: Option<String>,
     title: String,
     emoji: Option<String>,
     kind: Option<String>,
     scope: Option<String>,
     breaking: bool,
     body: String,
+    skip: bool,
+    group: Option<String>,
+    entry: Option<String>,
+    reverts: Option<String>,
+    patch_id: Option<String>,
+    cherry_picked_from: Option<String>,
 }
 
 impl ConvCommit {
     pub(crate) fn new(title: Option<&str>, body: Option<&str>) -> Self {
+        ConvCommit::with_parser(title, body, &CommitParser::default())
+    }
+
+    pub(crate) fn with_parser(
+        title: Option<&str>,
+        body: Option<&str>,
+        parser: &CommitParser,
+    ) -> Self {
         let mut cc = ConvCommit::default();
         if let Some(t) = title {
-            cc = ConvCommit::parse(t);
+            cc = ConvCommit::parse(t, parser);
         }
         if let Some(b) = body {
             cc.body = b.to_string();
+            cc.parse_directives();
+            cc.cherry_picked_from = CHERRY_PICKED_FROM
+                .captures(b)
+                .map(|c| c["hash"].to_lowercase());
+            if cc.is_revert() {
+                cc.reverts = REVERTED_COMMIT
+                    .captures(b)
+                    .map(|c| c["hash"].to_lowercase());
+            }
         }
         cc
     }
 
-    fn parse(title: &str) -> Self {
+    pub(crate) fn set_id(&mut self, id: Oid) -> &mut Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    pub(crate) fn set_patch_id(&mut self, patch_id: Oid) -> &mut Self {
+        self.patch_id = Some(patch_id.to_string());
+        self
+    }
+
+    /// The hash of the commit this commit was cherry-picked from
+    pub(crate) fn cherry_picked_from(&self) -> Option<&str> {
+        self.cherry_picked_from.as_deref()
+    }
+
+    pub(crate) fn set_cherry_picked_from(&mut self, origin: Oid) -> &mut Self {
+        self.cherry_picked_from = Some(origin.to_string());
+        self
+    }
+
+    /// Keys identifying the change made by the commit. Commits sharing a key
+    /// are copies of the same change.
+    pub(crate) fn change_keys(&self) -> Vec<String> {
+        let mut keys = Vec::new();
+        if let Some(id) = &self.id {
+            keys.push(format!("commit:{id}"));
+        }
+        if let Some(origin) = &self.cherry_picked_from {
+            keys.push(format!("commit:{origin}"));
+        }
+        if let Some(patch_id) = &self.patch_id {
+            keys.push(format!("patch:{patch_id}"));
+        }
+        keys
+    }
+
+    fn parse_directives(&mut self) {
+        for captures in DIRECTIVE.captures_iter(&self.body) {
+            let value = captures["value"].to_string();
+            match captures.name("key").map(|m| m.as_str().to_lowercase()) {
+                None => {
+                    if value.eq_ignore_ascii_case("skip") {
+                        self.skip = true;
+                    } else {
+                        log::warn!("unrecognised changelog directive `Changelog: {value}`");
+                    }
+                }
+                Some(key) if key == "group" => self.group = Some(value),
+                Some(_) => self.entry = Some(value),
+            }
+        }
+        log::trace!(
+            "Directives: skip `{}`, group `{:?}`, entry `{:?}`",
+            self.skip,
+            self.group,
+            self.entry
+        );
+    }
+
+    fn parse(title: &str, parser: &CommitParser) -> Self {
         log::trace!("String to parse: `{title}`");
 
-        let cmt_summary = if let Some(captures) = CONVENTIONAL.captures(title) {
+        let cmt_summary = if let Some(captures) = parser.captures(title) {
+            log::trace!("Custom parser captures: {captures:#?}");
+            ConvCommit::from_captures(title, &captures)
+        } else if let Some(captures) = REVERT.captures(title) {
+            Self {
+                title: captures["reverted"].to_string(),
+                kind: Some("revert".to_string()),
+                ..Default::default()
+            }
+        } else if let Some(captures) = CONVENTIONAL.captures(title) {
             log::trace!("Captures: {captures:#?}");
-            let emoji = captures.name("emoji").map(|m| m.as_str().to_string());
-            let kind = captures.name("type").map(|m| m.as_str().to_string());
-            let scope = captures.name("scope").map(|m| m.as_str().to_string());
-            let breaking = captures.name("breaking").is_some();
-            let title = captures
-                .name("description")
-                .map(|m| m.as_str().to_string())
-                .unwrap();
-
+            ConvCommit::from_captures(title, &captures)
+        } else if let Some((gitmoji, rest, group)) = parser.gitmoji(title) {
+            log::trace!("Gitmoji `{gitmoji}` mapped to group `{group}`");
             Self {
-                title,
-                emoji,
-                kind,
-                scope,
-                breaking,
-                body: String::new(),
+                title: rest.to_string(),
+                emoji: Some(format!("{gitmoji} ")),
+                group: Some(group.to_string()),
+                ..Default::default()
             }
         } else {
             Self {
                 title: title.to_string(),
+                ..Default::default()
+            }
+        };
+        let cmt_summary = if parser.strip_emoji() {
+            Self {
                 emoji: None,
-                kind: None,
-                scope: None,
-                breaking: false,
-                body: String::new(),
+                ..cmt_summary
             }
+        } else {
+            cmt_summary
         };
 
         log::trace!("Parsed title: {cmt_summary:?}");
@@ -77,6 +214,29 @@ impl ConvCommit {
         cmt_summary
     }
 
+    /// Build from the named captures `emoji`, `type`, `scope`, `breaking` and
+    /// `description`. Without a `description` the whole title is used.
+    fn from_captures(title: &str, captures: &Captures) -> Self {
+        let capture = |name: &str| {
+            captures
+                .name(name)
+                .map(|m| m.as_str())
+                .filter(|s| !s.is_empty())
+        };
+
+        Self {
+            title: captures
+                .name("description")
+                .map_or(title, |m| m.as_str())
+                .to_string(),
+            emoji: capture("emoji").map(|s| s.to_string()),
+            kind: capture("type").map(|s| s.to_string()),
+            scope: capture("scope").map(|s| s.to_string()),
+            breaking: capture("breaking").is_some(),
+            ..Default::default()
+        }
+    }
+
     pub(crate) fn is_conventional(&self) -> bool {
         self.kind.is_some()
     }
@@ -89,7 +249,56 @@ impl ConvCommit {
         self.scope.clone()
     }
 
+    pub(crate) fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Test if the commit id matches a full or abbreviated hash
+    pub(crate) fn has_id(&self, hash: &str) -> bool {
+        self.id.as_ref().is_some_and(|id| id.starts_with(hash))
+    }
+
+    pub(crate) fn is_revert(&self) -> bool {
+        self.kind
+            .as_deref()
+            .is_some_and(|k| k.eq_ignore_ascii_case("revert"))
+    }
+
+    /// The hash of the commit reverted by this commit
+    pub(crate) fn reverts(&self) -> Option<&str> {
+        self.reverts.as_deref()
+    }
+
+    /// The commit is marked with `Changelog: skip`
+    pub(crate) fn skip(&self) -> bool {
+        self.skip
+    }
+
+    /// The group named by a `Changelog-Group` directive or mapped to the
+    /// leading gitmoji
+    pub(crate) fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    /// Description of the commit without the `type(scope):` prefix, marked
+    /// when the change is breaking
+    pub(crate) fn description_as_string(&self) -> String {
+        format!(
+            "{}{}{}",
+            self.emoji.clone().unwrap_or_default(),
+            if self.breaking { "**BREAKING** " } else { "" },
+            self.entry.as_ref().unwrap_or(&self.title),
+        )
+    }
+
     pub(crate) fn title_as_string(&self) -> String {
+        if !self.is_conventional() {
+            return format!(
+                "{}{}",
+                self.emoji.clone().unwrap_or_default(),
+                self.entry.as_ref().unwrap_or(&self.title),
+            );
+        }
         format!(
             "{}{}{}{}: {}",
             self.emoji.clone().unwrap_or_default(),
@@ -98,7 +307,7 @@ impl ConvCommit {
                 .as_ref()
                 .map_or("".to_string(), |s| format!("({s})")),
             if self.breaking { "!" } else { "" },
-            self.title,
+            self.entry.as_ref().unwrap_or(&self.title),
         )
     }
 }
@@ -201,6 +410,101 @@ mod tests {
         assert!(!cmt_summary.breaking);
     }
 
+    #[test]
+    fn test_changelog_skip_directive() {
+        let commit = ConvCommit::new(
+            Some("feat: internal tooling"),
+            Some("Only used by CI.\n\nChangelog: skip\n"),
+        );
+        assert!(commit.skip());
+        assert_eq!(commit.group(), None);
+
+        let commit = ConvCommit::new(Some("feat: add search"), Some("changelog: SKIP"));
+        assert!(commit.skip());
+
+        let commit = ConvCommit::new(Some("feat: add search"), Some("Changelog: maybe"));
+        assert!(!commit.skip());
+    }
+
+    #[test]
+    fn test_changelog_group_and_entry_directives() {
+        let commit = ConvCommit::new(
+            Some("fix: handle tokens"),
+            Some(
+                "Details of the fix.\n\nChangelog-Group: Security\nChangelog-Entry: Redact access tokens in logs\nSigned-off-by: Jane Doe <jane@example.com>",
+            ),
+        );
+        assert!(!commit.skip());
+        assert_eq!(commit.group(), Some("Security"));
+        assert_eq!(
+            commit.title_as_string(),
+            "fix: Redact access tokens in logs"
+        );
+    }
+
+    #[test]
+    fn test_no_directives_keeps_title() {
+        let commit = ConvCommit::new(
+            Some("fix(core): handle tokens"),
+            Some("A changelog: is mentioned mid-line only."),
+        );
+        assert!(!commit.skip());
+        assert_eq!(commit.group(), None);
+        assert_eq!(commit.title_as_string(), "fix(core): handle tokens");
+    }
+
+    #[test]
+    fn test_git_revert_message() {
+        let commit = ConvCommit::new(
+            Some("Revert \"feat(core): add search\""),
+            Some("This reverts commit 0123456789abcdef0123456789abcdef01234567.\n"),
+        );
+        assert!(commit.is_revert());
+        assert_eq!(commit.kind, Some("revert".to_string()));
+        assert_eq!(commit.title, "feat(core): add search");
+        assert_eq!(
+            commit.reverts(),
+            Some("0123456789abcdef0123456789abcdef01234567")
+        );
+    }
+
+    #[test]
+    fn test_conventional_revert_type() {
+        let commit = ConvCommit::new(
+            Some("revert: remove search"),
+            Some("Search was slow.\n\nThis reverts commit 0123456."),
+        );
+        assert!(commit.is_revert());
+        assert_eq!(commit.title, "remove search");
+        assert_eq!(commit.reverts(), Some("0123456"));
+
+        let commit = ConvCommit::new(
+            Some("feat: add search"),
+            Some("This reverts commit 0123456."),
+        );
+        assert!(!commit.is_revert());
+        assert_eq!(commit.reverts(), None);
+    }
+
+    #[test]
+    fn test_cherry_picked_change_keys() {
+        let mut commit = ConvCommit::new(
+            Some("fix: crash"),
+            Some("(cherry picked from commit 0123456789abcdef0123456789abcdef01234567)"),
+        );
+        commit.set_id(Oid::from_str("1111111111111111111111111111111111111111").unwrap());
+        commit.set_patch_id(Oid::from_str("2222222222222222222222222222222222222222").unwrap());
+
+        assert_eq!(
+            commit.change_keys(),
+            vec![
+                "commit:1111111111111111111111111111111111111111".to_string(),
+                "commit:0123456789abcdef0123456789abcdef01234567".to_string(),
+                "patch:2222222222222222222222222222222222222222".to_string(),
+            ]
+        );
+    }
+
     #[rstest]
     #[case("feat: add new feature", "feat")]
     #[case("✨ feat: add new feature", "feat")]
@@ -0,0 +1,207 @@
+//! Commit Parser
+//!
+//! Holds the custom parser rules from the configuration, compiled once for the
+//! walk of the repository. The rules are tried in order and the first match is
+//! used to classify the commit summary. Summaries that match none of the rules
+//! fall back to the built-in conventional commit pattern.
+//!
+//! When gitmoji classification is enabled the parser also holds the table of
+//! emojis and shortcodes to groups used for summaries that are not
+//! conventional commits.
+
+use std::collections::HashMap;
+
+use lazy_regex::{Captures, Regex};
+
+use crate::{ChangeLogConfig, EmojiRender, Error, change_log_config::gitmoji::normalise_gitmoji};
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CommitParser {
+    rules: Vec<Regex>,
+    gitmoji: Option<HashMap<String, String>>,
+    strip_emoji: bool,
+}
+
+impl CommitParser {
+    pub(crate) fn new(config: &ChangeLogConfig) -> Result<Self, Error> {
+        let mut rules = Vec::new();
+        for rule in config.commit_parsers() {
+            let regex = Regex::new(rule.pattern())
+                .map_err(|e| Error::InvalidParserPattern(rule.pattern().to_string(), e))?;
+            rules.push(regex);
+        }
+        log::trace!("{} custom commit parser rules", rules.len());
+
+        let gitmoji = config
+            .gitmoji()
+            .enabled()
+            .then(|| config.gitmoji().group_table());
+        let strip_emoji = config.gitmoji().render() == EmojiRender::Strip;
+
+        Ok(CommitParser {
+            rules,
+            gitmoji,
+            strip_emoji,
+        })
+    }
+
+    /// Captures from the first custom rule matching the title
+    pub(crate) fn captures<'t>(&self, title: &'t str) -> Option<Captures<'t>> {
+        self.rules.iter().find_map(|rule| rule.captures(title))
+    }
+
+    /// Group mapped to the leading emoji or shortcode of the title, returned
+    /// with the gitmoji and the remainder of the title
+    pub(crate) fn gitmoji<'t>(&self, title: &'t str) -> Option<(&'t str, &'t str, &str)> {
+        let table = self.gitmoji.as_ref()?;
+        let (gitmoji, rest) = title.split_once(char::is_whitespace)?;
+        let group = table.get(&normalise_gitmoji(gitmoji))?;
+        Some((gitmoji, rest.trim_start(), group))
+    }
+
+    /// Emojis are removed from changelog entries
+    pub(crate) fn strip_emoji(&self) -> bool {
+        self.strip_emoji
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        Gitmoji, change_log::section::cc_commit::ConvCommit, change_log_config::ParserRule,
+    };
+
+    fn parser_for(patterns: &[&str]) -> CommitParser {
+        let mut config = ChangeLogConfig::default();
+        for pattern in patterns {
+            config.add_commit_parser(ParserRule::new(pattern));
+        }
+        CommitParser::new(&config).expect("valid patterns")
+    }
+
+    #[rstest]
+    #[case::jira(
+        r"^(?P<ticket>[A-Z]+-\d+) (?P<type>[a-z]+)(?:\((?P<scope>[^)]+)\))?(?P<breaking>!)?: (?P<description>.+)$",
+        "ABC-123 feat(api): add search",
+        "feat",
+        Some("api"),
+        "add search"
+    )]
+    #[case::bracketed(
+        r"^\[(?P<type>[A-Z]+)\] (?P<description>.+)$",
+        "[FIX] crash on start",
+        "FIX",
+        None,
+        "crash on start"
+    )]
+    fn test_custom_rule_classifies_summary(
+        #[case] pattern: &str,
+        #[case] summary: &str,
+        #[case] kind: &str,
+        #[case] scope: Option<&str>,
+        #[case] description: &str,
+    ) {
+        let parser = parser_for(&[pattern]);
+        let commit = ConvCommit::with_parser(Some(summary), None, &parser);
+
+        assert_eq!(commit.kind().as_deref(), Some(kind));
+        assert_eq!(commit.scope().as_deref(), scope);
+        assert!(commit.title_as_string().ends_with(description));
+    }
+
+    #[test]
+    fn test_rules_tried_in_order_before_default() {
+        let parser = parser_for(&[
+            r"^\[(?P<type>fix)\] (?P<description>.+)$",
+            r"^\[(?P<type>[a-z]+)\] (?P<description>.+)$",
+        ]);
+
+        let commit = ConvCommit::with_parser(Some("[fix] crash"), None, &parser);
+        assert_eq!(commit.kind().as_deref(), Some("fix"));
+
+        let commit = ConvCommit::with_parser(Some("[docs] readme"), None, &parser);
+        assert_eq!(commit.kind().as_deref(), Some("docs"));
+
+        // Falls back to the conventional commit parser
+        let commit = ConvCommit::with_parser(Some("feat!: new api"), None, &parser);
+        assert_eq!(commit.kind().as_deref(), Some("feat"));
+        assert_eq!(commit.title_as_string(), "feat!: new api");
+    }
+
+    #[rstest]
+    #[case::emoji("✨ add search", "Added", "✨ add search")]
+    #[case::shortcode(":bug: fix crash", "Fixed", ":bug: fix crash")]
+    #[case::no_variation_selector("⚡ faster walk", "Changed", "⚡ faster walk")]
+    #[case::configured(":rocket: deploy", "Continuous Integration", ":rocket: deploy")]
+    fn test_gitmoji_classifies_summary(
+        #[case] summary: &str,
+        #[case] group: &str,
+        #[case] entry: &str,
+    ) {
+        let mut config = ChangeLogConfig::default();
+        let mut gitmoji = Gitmoji::default();
+        gitmoji
+            .set_enabled(true)
+            .map_to_group(":rocket:", "Continuous Integration");
+        config.set_gitmoji(gitmoji);
+        let parser = CommitParser::new(&config).expect("valid config");
+
+        let commit = ConvCommit::with_parser(Some(summary), None, &parser);
+        assert!(!commit.is_conventional());
+        assert_eq!(commit.group(), Some(group));
+        assert_eq!(commit.title_as_string(), entry);
+    }
+
+    #[test]
+    fn test_gitmoji_disabled_by_default() {
+        let parser = CommitParser::new(&ChangeLogConfig::default()).expect("valid config");
+
+        let commit = ConvCommit::with_parser(Some("✨ add search"), None, &parser);
+        assert_eq!(commit.group(), None);
+        assert_eq!(commit.title_as_string(), "✨ add search");
+    }
+
+    #[test]
+    fn test_gitmoji_strip_and_overrides() {
+        let mut config = ChangeLogConfig::default();
+        let mut gitmoji = Gitmoji::default();
+        gitmoji.set_enabled(true).set_render(EmojiRender::Strip);
+        config.set_gitmoji(gitmoji);
+        let parser = CommitParser::new(&config).expect("valid config");
+
+        let commit = ConvCommit::with_parser(Some("✨ add search"), None, &parser);
+        assert_eq!(commit.group(), Some("Added"));
+        assert_eq!(commit.title_as_string(), "add search");
+
+        // Conventional commits keep their type and lose the emoji
+        let commit = ConvCommit::with_parser(Some("🐛 feat: add search"), None, &parser);
+        assert_eq!(commit.kind().as_deref(), Some("feat"));
+        assert_eq!(commit.group(), None);
+        assert_eq!(commit.title_as_string(), "feat: add search");
+
+        // A Changelog-Group trailer takes precedence over the gitmoji
+        let commit = ConvCommit::with_parser(
+            Some("✨ add search"),
+            Some("Body\n\nChangelog-Group: Changed\n"),
+            &parser,
+        );
+        assert_eq!(commit.group(), Some("Changed"));
+
+        // Unknown emojis are left in the title
+        let commit = ConvCommit::with_parser(Some("🦀 rewrite"), None, &parser);
+        assert_eq!(commit.group(), None);
+        assert_eq!(commit.title_as_string(), "🦀 rewrite");
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_an_error() {
+        let mut config = ChangeLogConfig::default();
+        config.add_commit_parser(ParserRule::new("^(?P<type>[a-z+: (.*)$"));
+
+        let result = CommitParser::new(&config);
+        assert!(matches!(result, Err(Error::InvalidParserPattern(_, _))));
+    }
+}
@@ -1,12 +1,12 @@
 use std::fmt::Display;
 
 use chrono::{DateTime, Utc};
-use git2::{ObjectType, Oid, Repository};
+use git2::{Oid, Repository};
 use lazy_regex::{Lazy, Regex, lazy_regex};
 use semver::Version;
 use thiserror::Error;
 
-use crate::change_log_config::ReleasePattern;
+use crate::{Error, change_log_config::ReleasePattern};
 
 // Both patterns are anchored (`^…$`) and are applied to tag names with the
 // `refs/tags/` ref prefix stripped (see `TagBuilder::get_semver`). Anchoring is
@@ -34,8 +34,8 @@ impl Tag {
         let date = chrono::Utc::now();
 
         match semver {
-            Ok(_) => {
-                let semver = Some(semver.unwrap());
+            Ok(semver) => {
+                let semver = Some(semver);
                 let date = Some(date);
                 Tag {
                     id: None,
@@ -84,6 +84,14 @@ impl Tag {
         self.semver.as_ref()
     }
 
+    /// The version of a release tag, as used in links and anchors
+    pub(crate) fn version_string(&self) -> Result<String, Error> {
+        self.semver
+            .as_ref()
+            .map(|v| v.to_string())
+            .ok_or_else(|| Error::BadTag(self.name.clone()))
+    }
+
     pub(crate) fn date(&self) -> Option<&DateTime<Utc>> {
         self.date.as_ref()
     }
@@ -203,13 +211,13 @@ impl<'a> TagBuilder<'a> {
     }
 
     pub(crate) fn get_date(&mut self) -> &mut Self {
-        if self.id.is_none() {
+        let Some(id) = self.id else {
             let date = Some(chrono::Utc::now());
             self.date = date;
             return self;
-        }
+        };
 
-        let Ok(git_tag) = self.repo.find_tag(self.id.unwrap()) else {
+        let Ok(git_tag) = self.repo.find_tag(id) else {
             return self;
         };
 
@@ -221,28 +229,19 @@ impl<'a> TagBuilder<'a> {
             }
         };
 
-        let Some(kind) = tag_object.kind() else {
+        if tag_object.kind().is_none() {
             log::warn!("object type not identified for {}", self.name);
             return self;
-        };
+        }
 
-        let commit;
-        match kind {
-            ObjectType::Commit => {
-                let c = tag_object.as_commit().unwrap();
-                commit = c.clone();
-            }
-            _ => {
-                let peel = tag_object.clone().peel_to_commit();
-                match peel {
-                    Ok(c) => commit = c,
-                    Err(e) => {
-                        log::warn!("Error peeling tag to commit {e}");
-                        return self;
-                    }
-                }
+        // Peeling a commit returns the commit itself
+        let commit = match tag_object.peel_to_commit() {
+            Ok(c) => c,
+            Err(e) => {
+                log::warn!("Error peeling tag to commit {e}");
+                return self;
             }
-        }
+        };
 
         let time = commit.time();
         let date = chrono::DateTime::from_timestamp(time.seconds(), 0);
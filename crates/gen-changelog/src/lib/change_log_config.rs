@@ -4,24 +4,43 @@ use std::{
     path::PathBuf,
 };
 
+mod author_filter;
+mod document;
+pub(crate) mod gitmoji;
+mod glob;
 mod group;
 mod group_mgmt;
 mod heading_serde;
+mod layers;
+mod lint_rules;
+mod parser_rule;
+mod routing_rule;
+mod scope_groups;
 mod test_config_serialization;
+mod validate;
 
 pub(crate) mod heading_mgmt;
 
+pub use author_filter::AuthorFilter;
+pub use document::ConfigDocument;
+pub use gitmoji::{EmojiRender, Gitmoji};
 use group::Group;
 use group_mgmt::GroupMgmt;
 use heading_mgmt::HeadingMgmt;
+pub use layers::{ConfigLayers, ConfigSource};
+pub use lint_rules::LintRules;
+pub use parser_rule::ParserRule;
+pub use routing_rule::RoutingRule;
+pub use scope_groups::{ScopeGroups, ScopeMode};
 use serde::{Deserialize, Serialize};
 use titlecase::Titlecase;
+pub use validate::{Diagnostic, Severity};
 
 use crate::Error;
 
 /// Default groups configuration with their conventional commit types and
 /// publish flags
-const DEFAULT_GROUPS: [(&str, &[&str; 2], bool); 12] = [
+const DEFAULT_GROUPS: [(&str, &[&str], bool); 14] = [
     ("Added", &["feat", "feat"], true),
     ("Fixed", &["fix", "fix"], true),
     ("Changed", &["refactor", "refactor"], true),
@@ -33,9 +52,15 @@ const DEFAULT_GROUPS: [(&str, &[&str; 2], bool); 12] = [
     ("Testing", &["test", "test"], false),
     ("Deprecated", &["deprecated", "deprecated"], false),
     ("Removed", &["removed", "removed"], false),
+    ("Reverted", &["revert", "revert"], true),
     ("Miscellaneous", &["misc", "misc"], false),
+    ("Unknown", &[], false),
 ];
 
+/// Default name of the group receiving commits that are not conventional
+/// commits
+const DEFAULT_UNKNOWN_GROUP: &str = "Unknown";
+
 /// Default configuration file name
 const DEFAULT_CONFIG_FILE: &str = "gen-changelog.toml";
 
@@ -82,6 +107,122 @@ const INCLUDE_MERGE_COMMITS_COMMENT: &str = r#"# Controls whether merge commits
 # Set to true to include merge commits in the generated changelog.
 "#;
 
+/// Documentation comment for first-parent in generated TOML
+const FIRST_PARENT_COMMENT: &str = r#"# Controls whether only the mainline (first-parent) history is walked.
+# Suited to merge-based workflows: the commits of merged branches are not
+# listed and each merge commit is classified from the pull request title in
+# its message or, when there is none, from the first conventional commit of
+# the merged branch.
+"#;
+
+/// Documentation comment for cherry-pick-policy in generated TOML
+const CHERRY_PICK_POLICY_COMMENT: &str = r#"# Controls how a change that appears in more than one section is listed.
+# Cherry-picked commits are identified by their patch-id or by the
+# `(cherry picked from commit ...)` line added by `git cherry-pick -x`:
+#   - "earliest": list the change once, in the earliest release containing it
+#   - "latest": list the change once, in the latest release containing it
+#   - "all": list every copy of the change (the default)
+"#;
+
+/// Documentation comment for commit-parsers in generated TOML
+const COMMIT_PARSERS_COMMENT: &str = r#"# Custom rules to classify commit summaries that do not use the conventional
+# commit format. Each rule is a regular expression tried in order before the
+# built-in conventional commit parser. Named captures identify the parts of
+# the summary:
+#   - type: the commit type used to find the group (see cc-types)
+#   - scope: the optional scope of the commit
+#   - breaking: matches when the commit is a breaking change
+#   - description: the text listed in the changelog (defaults to the summary)
+#
+# Example for JIRA prefixed summaries such as `ABC-123 feat: add search`,
+# replacing the empty list below:
+# [[commit-parsers]]
+# pattern = '^[A-Z]+-\d+ (?P<type>[a-z]+)(?:\((?P<scope>[^)]+)\))?(?P<breaking>!)?: (?P<description>.+)$'
+"#;
+
+/// Documentation comment for routing-rules in generated TOML
+const ROUTING_RULES_COMMENT: &str = r#"# Rules listing conventional commits of a type and scope under a group.
+# The rules are tried in order before the group mapping of the commit type:
+#   - type: glob pattern for the commit type
+#   - scope: optional glob pattern for the commit scope; without it the rule
+#     matches every commit of the type
+#   - group: group that receives the matching commits
+#
+# By default `chore(deps)` commits are listed under Security. Edit or remove
+# the rule below to change this, or set `routing-rules = []`.
+"#;
+
+/// Documentation comment for scope-groups section in generated TOML
+const SCOPE_GROUPS_COMMENT: &str = r#"# Sub-groups the commits listed under each heading by their scope.
+#   - mode: "off", "headings" for a fourth-level heading per scope, or "bold"
+#     to prefix each commit with its scope in bold
+#   - order: scopes listed first, in this order; other scopes follow sorted
+#     by name and commits without a scope are listed before all scopes
+#   - names: scopes mapped to the name displayed in the changelog
+#
+# Example:
+# [scope-groups]
+# mode = "headings"
+# order = ["core", "cli"]
+#
+# [scope-groups.names]
+# core = "Core library"
+"#;
+
+/// Documentation comment for unknown-group in generated TOML
+const UNKNOWN_GROUP_COMMENT: &str = r#"# The group receiving commits that are not conventional commits and are not
+# classified by a commit parser, gitmoji or `Changelog-Group` trailer.
+# The group is not published by default. To list these commits in the
+# changelog set `publish = true` on the group and add it to the headings.
+"#;
+
+/// Documentation comment for type-aliases section in generated TOML
+const TYPE_ALIASES_COMMENT: &str = r#"# Aliases for conventional commit types.
+# Commit types are matched case-insensitively and an alias is replaced by its
+# type before the group of the commit is looked up in cc-types, so that e.g.
+# `Feature:` and `FEAT:` are both listed with `feat` commits.
+"#;
+
+/// Documentation comment for lint section in generated TOML
+const LINT_COMMENT: &str = r#"# Rules for the `lint` command that checks commit messages.
+# Commit types are checked against the cc-types of the groups and their
+# aliases, and footers against the git trailer format (`Token: value`).
+#   - max-subject-length: maximum number of characters in the subject line
+#   - scopes: glob patterns for the allowed scopes; any scope when empty
+#   - require-scope: commits must have a scope
+"#;
+
+/// Documentation comment for author-filter section in generated TOML
+const AUTHOR_FILTER_COMMENT: &str = r#"# Filters commits by the name or email of their author.
+# Patterns are globs (`*` and `?`) matched case-insensitively against both the
+# author name and email:
+#   - include: when not empty, only commits by matching authors are kept
+#   - exclude: commits by matching authors are excluded
+#   - group: optional group that receives excluded commits instead of dropping them
+#
+# Example:
+# [author-filter]
+# exclude = ["*[bot]", "*[bot]@users.noreply.github.com"]
+# group = "Dependencies"
+"#;
+
+/// Documentation comment for gitmoji section in generated TOML
+const GITMOJI_COMMENT: &str = r#"# Classifies commits that start with a gitmoji (https://gitmoji.dev) instead
+# of a conventional commit type, e.g. `✨ add search` or `:bug: fix crash`.
+#   - enabled: classify commits by their leading emoji or shortcode
+#   - render: "keep" or "strip" the emoji in changelog entries
+#   - groups: emojis or shortcodes mapped to groups, adding to or replacing
+#     the default mapping of the official gitmoji list
+#
+# Example:
+# [gitmoji]
+# enabled = true
+# render = "strip"
+#
+# [gitmoji.groups]
+# ":rocket:" = "Continuous Integration"
+"#;
+
 /// Configures how many changelog sections to display in the generated output.
 ///
 /// Each section typically represents a version or release, with the
@@ -129,6 +270,26 @@ pub enum ReleasePattern {
     PackagePrefix(String),
 }
 
+/// Policy for listing changes that appear in more than one section, such as
+/// fixes cherry-picked onto a maintenance branch.
+///
+/// Duplicates are identified by their git patch-id or by the
+/// `(cherry picked from commit …)` line added by `git cherry-pick -x`. Only
+/// the sections generated for the changelog are compared, and a commit that
+/// is reverted in those sections is not compared, so a change landed again
+/// after a revert is listed in both releases.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CherryPickPolicy {
+    /// List the change once, in the earliest release that contains it
+    Earliest,
+    /// List the change once, in the latest release that contains it
+    Latest,
+    /// List every copy of the change
+    #[default]
+    All,
+}
+
 /// Main configuration structure for changelog generation.
 ///
 /// This struct controls all aspects of changelog generation including:
@@ -172,7 +333,7 @@ pub enum ReleasePattern {
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields, default)]
 #[serde(rename_all = "kebab-case")]
 pub struct ChangeLogConfig {
@@ -203,6 +364,59 @@ pub struct ChangeLogConfig {
     /// because they typically duplicate their constituent commits.
     include_merge_commits: bool,
 
+    /// Controls whether only the first-parent history is walked.
+    ///
+    /// Merge commits on the first-parent history are classified from the pull
+    /// request title or a summary of the merged commits.
+    first_parent: bool,
+
+    /// Aliases for commit types, mapping an alias to the type it stands for.
+    ///
+    /// There are no aliases by default.
+    #[serde(deserialize_with = "deserialize_type_aliases")]
+    type_aliases: BTreeMap<String, String>,
+
+    /// Name of the group receiving commits that are not conventional commits.
+    ///
+    /// Defaults to the "Unknown" group, which is not published.
+    unknown_group: String,
+
+    /// Filters commits by the name or email of their author.
+    ///
+    /// Used to drop commits made by bots and release automation, or to route
+    /// them to a dedicated group.
+    author_filter: AuthorFilter,
+
+    /// Controls how changes that appear in more than one section are listed.
+    ///
+    /// By default every copy of a cherry-picked change is listed.
+    cherry_pick_policy: CherryPickPolicy,
+
+    /// Custom rules used to classify commit summaries.
+    ///
+    /// The rules are tried in order before falling back to the built-in
+    /// conventional commit parser.
+    commit_parsers: Vec<ParserRule>,
+
+    /// Rules routing conventional commits to groups by type and scope.
+    ///
+    /// By default `chore(deps)` commits are routed to "Security".
+    routing_rules: Vec<RoutingRule>,
+
+    /// Sub-grouping of the commits under each heading by their scope.
+    ///
+    /// Disabled by default.
+    scope_groups: ScopeGroups,
+
+    /// Rules applied to commit messages by the linter.
+    lint: LintRules,
+
+    /// Classification of commits by a leading gitmoji.
+    ///
+    /// Disabled by default; when enabled, summaries that are not conventional
+    /// commits are grouped by their leading emoji or shortcode.
+    gitmoji: Gitmoji,
+
     /// Pattern used to identify Git tags as release tags.
     ///
     /// This field is not serialized to/from configuration files and uses
@@ -216,9 +430,10 @@ impl Default for ChangeLogConfig {
     /// groups.
     ///
     /// The default configuration includes:
-    /// - 12 predefined groups for common conventional commit types
-    /// - Only "Added", "Fixed", "Changed", and "Security" groups are published
-    ///   by default
+    /// - 13 predefined groups for common conventional commit types and an
+    ///   "Unknown" group for commits that are not conventional commits
+    /// - Only "Added", "Fixed", "Changed", "Security" and "Reverted" groups are
+    ///   published by default
     /// - Display all sections
     /// - Use "v" prefix for release tag identification
     fn default() -> Self {
@@ -250,6 +465,7 @@ impl Default for ChangeLogConfig {
         headings.add_heading("Fixed");
         headings.add_heading("Changed");
         headings.add_heading("Security");
+        headings.add_heading("Reverted");
 
         log::trace!("default headings to publish {headings:?}");
 
@@ -260,6 +476,16 @@ impl Default for ChangeLogConfig {
             headings,
             display_sections: DisplaySections::default(),
             include_merge_commits: false,
+            first_parent: false,
+            type_aliases: BTreeMap::new(),
+            unknown_group: DEFAULT_UNKNOWN_GROUP.to_string(),
+            author_filter: AuthorFilter::default(),
+            cherry_pick_policy: CherryPickPolicy::default(),
+            commit_parsers: Vec::new(),
+            routing_rules: vec![RoutingRule::new("chore", Some("deps"), "Security")],
+            scope_groups: ScopeGroups::default(),
+            lint: LintRules::default(),
+            gitmoji: Gitmoji::default(),
             release_pattern,
         }
     }
@@ -308,7 +534,8 @@ impl ChangeLogConfig {
     /// # Returns
     ///
     /// * `Ok(ChangeLogConfig)` - The loaded configuration
-    /// * `Err(Error)` - If the file cannot be read or the TOML cannot be parsed
+    /// * `Err(Error)` - If the file does not exist, cannot be read or the TOML
+    ///   cannot be parsed
     ///
     /// # Examples
     ///
@@ -324,7 +551,11 @@ impl ChangeLogConfig {
     /// # }
     /// ```
     pub fn from_file<P: Into<PathBuf>>(path: P) -> Result<Self, Error> {
-        let file = read_to_string(path.into())?;
+        let path = path.into();
+        if !path.is_file() {
+            return Err(Error::ConfigNotFound(path));
+        }
+        let file = read_to_string(path)?;
         Ok(toml::from_str::<ChangeLogConfig>(&file)?)
     }
 
@@ -354,7 +585,8 @@ impl ChangeLogConfig {
     ///
     /// This creates a new BTreeMap where each key is a conventional commit type
     /// (like "feat", "fix") and each value is the group name it belongs to
-    /// (like "Added", "Fixed").
+    /// (like "Added", "Fixed"). Types are lowercased so that they can be
+    /// looked up with a type normalised by [`ChangeLogConfig::normalise_type`].
     ///
     /// # Returns
     ///
@@ -374,7 +606,7 @@ impl ChangeLogConfig {
         let mut groups_mapping = BTreeMap::new();
         for g in self.groups.values() {
             for key in g.cc_types() {
-                groups_mapping.insert(key.to_string(), g.name().to_string());
+                groups_mapping.insert(key.to_lowercase(), g.name().to_string());
             }
         }
         groups_mapping
@@ -430,6 +662,40 @@ impl ChangeLogConfig {
         if let Some(idx) = toml_string.find("include-merge-commits") {
             toml_string.insert_str(idx, INCLUDE_MERGE_COMMITS_COMMENT)
         }
+        if let Some(idx) = toml_string.find("first-parent") {
+            toml_string.insert_str(idx, FIRST_PARENT_COMMENT)
+        }
+        if let Some(idx) = toml_string.find("[type-aliases]") {
+            toml_string.insert_str(idx, TYPE_ALIASES_COMMENT)
+        }
+        if let Some(idx) = toml_string.find("unknown-group") {
+            toml_string.insert_str(idx, UNKNOWN_GROUP_COMMENT)
+        }
+        if let Some(idx) = toml_string.find("cherry-pick-policy") {
+            toml_string.insert_str(idx, CHERRY_PICK_POLICY_COMMENT)
+        }
+        if let Some(idx) = toml_string.find("[[commit-parsers]]") {
+            toml_string.insert_str(idx, COMMIT_PARSERS_COMMENT)
+        } else if let Some(idx) = toml_string.find("commit-parsers") {
+            toml_string.insert_str(idx, COMMIT_PARSERS_COMMENT)
+        }
+        if let Some(idx) = toml_string.find("[[routing-rules]]") {
+            toml_string.insert_str(idx, ROUTING_RULES_COMMENT)
+        } else if let Some(idx) = toml_string.find("routing-rules") {
+            toml_string.insert_str(idx, ROUTING_RULES_COMMENT)
+        }
+        if let Some(idx) = toml_string.find("[author-filter]") {
+            toml_string.insert_str(idx, AUTHOR_FILTER_COMMENT)
+        }
+        if let Some(idx) = toml_string.find("[scope-groups]") {
+            toml_string.insert_str(idx, SCOPE_GROUPS_COMMENT)
+        }
+        if let Some(idx) = toml_string.find("[lint]") {
+            toml_string.insert_str(idx, LINT_COMMENT)
+        }
+        if let Some(idx) = toml_string.find("[gitmoji]") {
+            toml_string.insert_str(idx, GITMOJI_COMMENT)
+        }
 
         if let Some(f) = file {
             std::fs::write(f, toml_string)?;
@@ -578,6 +844,38 @@ impl ChangeLogConfig {
         self
     }
 
+    /// Defines a published group listing commits of the given types, replacing
+    /// any group of the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `group_name` - Name of the group, used as the heading
+    /// * `cc_types` - Conventional commit types listed under the group
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gen_changelog::ChangeLogConfig;
+    ///
+    /// let mut config = ChangeLogConfig::default();
+    /// config.define_group("Performance", &["perf".to_string()]);
+    /// assert!(config.has_group("Performance"));
+    /// ```
+    pub fn define_group(&mut self, group_name: &str, cc_types: &[String]) -> &mut Self {
+        let cc_types = cc_types.iter().map(String::as_str).collect::<Vec<_>>();
+        let group = Group::new_with_name_types_and_publish_flag(group_name, &cc_types, true);
+        self.add_group(group)
+    }
+
+    /// Returns true when a group of that name is defined.
+    pub fn has_group(&self, group_name: &str) -> bool {
+        self.groups.contains_key(group_name)
+    }
+
     /// Returns a reference to the release pattern configuration.
     ///
     /// The release pattern determines which Git tags are considered release
@@ -677,6 +975,379 @@ impl ChangeLogConfig {
         log::debug!("Include merge commits: `{}`", self.include_merge_commits);
         self
     }
+
+    /// Returns whether only the first-parent history is walked.
+    ///
+    /// Commits on merged branches are left out and each merge commit stands
+    /// for the changes it merged.
+    pub fn first_parent(&self) -> bool {
+        self.first_parent
+    }
+
+    /// Sets whether only the first-parent history is walked.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Whether to walk only the first-parent history
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gen_changelog::ChangeLogConfig;
+    ///
+    /// let mut config = ChangeLogConfig::default();
+    /// config.set_first_parent(true);
+    /// assert!(config.first_parent());
+    /// ```
+    pub fn set_first_parent(&mut self, value: bool) -> &mut Self {
+        self.first_parent = value;
+        log::debug!("First parent: `{}`", self.first_parent);
+        self
+    }
+
+    /// Returns the aliases for commit types, mapping each alias to its type.
+    pub fn type_aliases(&self) -> &BTreeMap<String, String> {
+        &self.type_aliases
+    }
+
+    /// Adds an alias for a commit type.
+    ///
+    /// # Arguments
+    ///
+    /// * `alias` - The type as written in commit messages
+    /// * `kind` - The type the alias stands for
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gen_changelog::ChangeLogConfig;
+    ///
+    /// let mut config = ChangeLogConfig::default();
+    /// config.add_type_alias("enhancement", "feat");
+    /// assert_eq!(config.normalise_type("Enhancement"), "feat");
+    /// ```
+    pub fn add_type_alias(&mut self, alias: &str, kind: &str) -> &mut Self {
+        self.type_aliases
+            .insert(alias.to_lowercase(), kind.to_lowercase());
+        log::debug!("Type aliases: `{:?}`", self.type_aliases);
+        self
+    }
+
+    /// Normalises a commit type as written in a commit message by lowercasing
+    /// it and replacing an alias with the type it stands for.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gen_changelog::ChangeLogConfig;
+    ///
+    /// let mut config = ChangeLogConfig::default();
+    /// config.add_type_alias("feature", "feat");
+    /// assert_eq!(config.normalise_type("FIX"), "fix");
+    /// assert_eq!(config.normalise_type("Feature"), "feat");
+    /// ```
+    pub fn normalise_type(&self, kind: &str) -> String {
+        normalise_type(&self.type_aliases, kind)
+    }
+
+    /// Returns the name of the group receiving commits that are not
+    /// conventional commits.
+    pub fn unknown_group(&self) -> &str {
+        &self.unknown_group
+    }
+
+    /// Sets the group receiving commits that are not conventional commits.
+    ///
+    /// The group is listed in the changelog when it is published, see
+    /// [`ChangeLogConfig::publish_group`].
+    ///
+    /// # Arguments
+    ///
+    /// * `group_name` - Name of the group to receive the commits
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gen_changelog::ChangeLogConfig;
+    ///
+    /// let mut config = ChangeLogConfig::default();
+    /// config
+    ///     .set_unknown_group("Miscellaneous")
+    ///     .publish_group("Miscellaneous");
+    /// ```
+    pub fn set_unknown_group(&mut self, group_name: &str) -> &mut Self {
+        self.unknown_group = group_name.to_string();
+        log::debug!("Unknown group: `{}`", self.unknown_group);
+        self
+    }
+
+    /// Returns a reference to the author filter configuration.
+    ///
+    /// The author filter determines which commits are excluded from the
+    /// changelog, or routed to a dedicated group, based on their author.
+    pub fn author_filter(&self) -> &AuthorFilter {
+        &self.author_filter
+    }
+
+    /// Sets the filter applied to commit authors.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The author filter to apply
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gen_changelog::{AuthorFilter, ChangeLogConfig};
+    ///
+    /// let mut filter = AuthorFilter::default();
+    /// filter.add_exclude("*[bot]");
+    ///
+    /// let mut config = ChangeLogConfig::default();
+    /// config.set_author_filter(filter);
+    /// ```
+    pub fn set_author_filter(&mut self, filter: AuthorFilter) -> &mut Self {
+        self.author_filter = filter;
+        log::debug!("Author filter: `{:?}`", self.author_filter);
+        self
+    }
+
+    /// Returns the policy for listing changes that appear in more than one
+    /// section.
+    pub fn cherry_pick_policy(&self) -> CherryPickPolicy {
+        self.cherry_pick_policy
+    }
+
+    /// Sets the policy for listing changes that appear in more than one
+    /// section.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy to apply to duplicate changes
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining
+    pub fn set_cherry_pick_policy(&mut self, policy: CherryPickPolicy) -> &mut Self {
+        self.cherry_pick_policy = policy;
+        log::debug!("Cherry pick policy: `{:?}`", self.cherry_pick_policy);
+        self
+    }
+
+    /// Returns the custom rules used to classify commit summaries.
+    pub fn commit_parsers(&self) -> &[ParserRule] {
+        &self.commit_parsers
+    }
+
+    /// Adds a custom rule to classify commit summaries.
+    ///
+    /// Rules are tried in the order they are added, before the built-in
+    /// conventional commit parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The parser rule to add
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gen_changelog::{ChangeLogConfig, ParserRule};
+    ///
+    /// let mut config = ChangeLogConfig::default();
+    /// config.add_commit_parser(ParserRule::new(
+    ///     r"^\[(?P<type>[A-Z]+)\] (?P<description>.+)$",
+    /// ));
+    /// ```
+    pub fn add_commit_parser(&mut self, rule: ParserRule) -> &mut Self {
+        self.commit_parsers.push(rule);
+        self
+    }
+
+    /// Returns the rules routing conventional commits to groups by type and
+    /// scope.
+    pub fn routing_rules(&self) -> &[RoutingRule] {
+        &self.routing_rules
+    }
+
+    /// Adds a rule routing conventional commits to a group by type and scope.
+    ///
+    /// Rules are tried in the order they are added, before the group mapping
+    /// of the commit type.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The routing rule to add
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gen_changelog::{ChangeLogConfig, RoutingRule};
+    ///
+    /// let mut config = ChangeLogConfig::default();
+    /// config
+    ///     .clear_routing_rules()
+    ///     .add_routing_rule(RoutingRule::new("build", Some("deps*"), "Security"));
+    /// ```
+    pub fn add_routing_rule(&mut self, rule: RoutingRule) -> &mut Self {
+        self.routing_rules.push(rule);
+        log::debug!("Routing rules: `{:?}`", self.routing_rules);
+        self
+    }
+
+    /// Removes all routing rules, including the default `chore(deps)` rule.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining
+    pub fn clear_routing_rules(&mut self) -> &mut Self {
+        self.routing_rules.clear();
+        self
+    }
+
+    /// Returns a reference to the scope sub-grouping configuration.
+    pub fn scope_groups(&self) -> &ScopeGroups {
+        &self.scope_groups
+    }
+
+    /// Sets the sub-grouping of the commits under each heading by scope.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope_groups` - The scope sub-grouping configuration to apply
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gen_changelog::{ChangeLogConfig, ScopeGroups, ScopeMode};
+    ///
+    /// let mut scope_groups = ScopeGroups::default();
+    /// scope_groups
+    ///     .set_mode(ScopeMode::Headings)
+    ///     .set_order(&["core", "cli"])
+    ///     .set_name("core", "Core library");
+    ///
+    /// let mut config = ChangeLogConfig::default();
+    /// config.set_scope_groups(scope_groups);
+    /// ```
+    pub fn set_scope_groups(&mut self, scope_groups: ScopeGroups) -> &mut Self {
+        self.scope_groups = scope_groups;
+        log::debug!("Scope groups: `{:?}`", self.scope_groups);
+        self
+    }
+
+    /// Returns a reference to the rules applied to commit messages by the
+    /// linter.
+    pub fn lint(&self) -> &LintRules {
+        &self.lint
+    }
+
+    /// Sets the rules applied to commit messages by the linter.
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - The lint rules to apply
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gen_changelog::{ChangeLogConfig, LintRules};
+    ///
+    /// let mut rules = LintRules::default();
+    /// rules.set_max_subject_length(50).add_scope("core");
+    ///
+    /// let mut config = ChangeLogConfig::default();
+    /// config.set_lint(rules);
+    /// ```
+    pub fn set_lint(&mut self, rules: LintRules) -> &mut Self {
+        self.lint = rules;
+        log::debug!("Lint rules: `{:?}`", self.lint);
+        self
+    }
+
+    /// Returns a reference to the gitmoji classification configuration.
+    pub fn gitmoji(&self) -> &Gitmoji {
+        &self.gitmoji
+    }
+
+    /// Sets the classification of commits by a leading gitmoji.
+    ///
+    /// # Arguments
+    ///
+    /// * `gitmoji` - The gitmoji configuration to apply
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gen_changelog::{ChangeLogConfig, EmojiRender, Gitmoji};
+    ///
+    /// let mut gitmoji = Gitmoji::default();
+    /// gitmoji.set_enabled(true).set_render(EmojiRender::Strip);
+    ///
+    /// let mut config = ChangeLogConfig::default();
+    /// config.set_gitmoji(gitmoji);
+    /// ```
+    pub fn set_gitmoji(&mut self, gitmoji: Gitmoji) -> &mut Self {
+        self.gitmoji = gitmoji;
+        log::debug!("Gitmoji: `{:?}`", self.gitmoji);
+        self
+    }
+}
+
+/// Lowercase the commit type and replace an alias with the type it stands for
+///
+/// The aliases are stored in lowercase.
+pub(crate) fn normalise_type(aliases: &BTreeMap<String, String>, kind: &str) -> String {
+    let kind = kind.to_lowercase();
+    aliases.get(&kind).cloned().unwrap_or(kind)
+}
+
+/// Deserialize the type aliases in lowercase, so that they are matched with a
+/// lookup of the lowercased commit type
+fn deserialize_type_aliases<'de, D>(deserializer: D) -> Result<BTreeMap<String, String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let aliases = BTreeMap::<String, String>::deserialize(deserializer)?;
+    Ok(aliases
+        .into_iter()
+        .map(|(alias, kind)| (alias.to_lowercase(), kind.to_lowercase()))
+        .collect())
 }
 
 #[cfg(test)]
@@ -870,7 +1541,7 @@ cc-types = ["custom"]
     #[test]
     fn test_from_file_nonexistent() {
         let result = ChangeLogConfig::from_file("nonexistent-file.toml");
-        assert!(result.is_err());
+        assert!(matches!(result, Err(Error::ConfigNotFound(_))));
     }
 
     #[test]
@@ -1131,6 +1802,7 @@ cc-types = ["custom"]
             Some(&"Continuous Integration".to_string())
         );
         assert_eq!(mapping.get("test"), Some(&"Testing".to_string()));
+        assert_eq!(mapping.get("revert"), Some(&"Reverted".to_string()));
     }
 
     #[test]
@@ -1167,6 +1839,16 @@ cc-types = ["custom"]
         assert!(deserialized.include_merge_commits());
     }
 
+    #[test]
+    fn test_first_parent_from_toml() {
+        let config: ChangeLogConfig =
+            toml::from_str("first-parent = true").expect("Failed to deserialize");
+        assert!(config.first_parent());
+
+        let config: ChangeLogConfig = toml::from_str("").expect("Failed to deserialize");
+        assert!(!config.first_parent());
+    }
+
     #[test]
     fn test_include_merge_commits_missing_from_toml() {
         // Existing config files without include-merge-commits should default to false
@@ -1184,6 +1866,176 @@ cc-types = ["feat"]
         assert!(!config.include_merge_commits());
     }
 
+    #[test]
+    fn test_type_aliases() {
+        let config: ChangeLogConfig = toml::from_str("").expect("Failed to deserialize");
+        assert!(config.type_aliases().is_empty());
+        assert_eq!(config.normalise_type("Feat"), "feat");
+        assert_eq!(config.normalise_type("BUGFIX"), "bugfix");
+        assert_eq!(config.normalise_type("perf"), "perf");
+
+        let toml_content = r#"
+[type-aliases]
+Enhancement = "FEAT"
+"#;
+        let config: ChangeLogConfig = toml::from_str(toml_content).expect("Failed to deserialize");
+        assert_eq!(
+            config.type_aliases().get("enhancement"),
+            Some(&"feat".to_string())
+        );
+        assert_eq!(config.normalise_type("enhancement"), "feat");
+    }
+
+    #[test]
+    fn test_unknown_group() {
+        let mut config = ChangeLogConfig::default();
+        assert_eq!(config.unknown_group(), "Unknown");
+        assert!(config.groups.contains_key("Unknown"));
+        assert!(!config.headings().contains("Unknown"));
+
+        config.add_commit_groups(&["unknown".to_string()]);
+        assert!(config.groups["Unknown"].publish());
+        assert!(config.headings().contains("Unknown"));
+
+        let toml_content = r#"
+unknown-group = "Other"
+
+[groups.Other]
+name = "Other"
+publish = true
+
+[headings]
+Other = 1
+Added = 2
+"#;
+        let config: ChangeLogConfig = toml::from_str(toml_content).expect("Failed to deserialize");
+        assert_eq!(config.unknown_group(), "Other");
+        assert_eq!(config.headings().get(&1), Some(&"Other".to_string()));
+    }
+
+    #[test]
+    fn test_author_filter_from_toml() {
+        let toml_content = r#"
+[author-filter]
+exclude = ["*[bot]"]
+group = "Dependencies"
+"#;
+
+        let config: ChangeLogConfig = toml::from_str(toml_content).expect("Failed to deserialize");
+        assert_eq!(config.author_filter().exclude(), ["*[bot]".to_string()]);
+        assert_eq!(config.author_filter().group(), Some("Dependencies"));
+
+        // Missing table defaults to a filter that keeps every author
+        let config: ChangeLogConfig = toml::from_str("").expect("Failed to deserialize");
+        assert!(config.author_filter().exclude().is_empty());
+        assert!(config.author_filter().group().is_none());
+    }
+
+    #[test]
+    fn test_cherry_pick_policy_from_toml() {
+        let config: ChangeLogConfig = toml::from_str("").expect("Failed to deserialize");
+        assert_eq!(config.cherry_pick_policy(), CherryPickPolicy::All);
+
+        let config: ChangeLogConfig =
+            toml::from_str(r#"cherry-pick-policy = "earliest""#).expect("Failed to deserialize");
+        assert_eq!(config.cherry_pick_policy(), CherryPickPolicy::Earliest);
+
+        let result: Result<ChangeLogConfig, _> = toml::from_str(r#"cherry-pick-policy = "first""#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_commit_parsers_from_toml() {
+        let toml_content = r#"
+[[commit-parsers]]
+pattern = '^\[(?P<type>[A-Z]+)\] (?P<description>.+)$'
+
+[[commit-parsers]]
+pattern = '^[A-Z]+-\d+ (?P<type>[a-z]+): (?P<description>.+)$'
+"#;
+
+        let config: ChangeLogConfig = toml::from_str(toml_content).expect("Failed to deserialize");
+        assert_eq!(config.commit_parsers().len(), 2);
+        assert_eq!(
+            config.commit_parsers()[0].pattern(),
+            r"^\[(?P<type>[A-Z]+)\] (?P<description>.+)$"
+        );
+    }
+
+    #[test]
+    fn test_routing_rules_from_toml() {
+        // The default rule routes chore(deps) to Security
+        let config: ChangeLogConfig = toml::from_str("").expect("Failed to deserialize");
+        assert_eq!(
+            config.routing_rules(),
+            [RoutingRule::new("chore", Some("deps"), "Security")]
+        );
+
+        let config: ChangeLogConfig =
+            toml::from_str("routing-rules = []").expect("Failed to deserialize");
+        assert!(config.routing_rules().is_empty());
+
+        let toml_content = r#"
+[[routing-rules]]
+type = "build"
+scope = "deps*"
+group = "Dependencies"
+
+[[routing-rules]]
+type = "perf"
+group = "Changed"
+"#;
+        let config: ChangeLogConfig = toml::from_str(toml_content).expect("Failed to deserialize");
+        assert_eq!(
+            config.routing_rules(),
+            [
+                RoutingRule::new("build", Some("deps*"), "Dependencies"),
+                RoutingRule::new("perf", None, "Changed"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scope_groups_from_toml() {
+        let config: ChangeLogConfig = toml::from_str("").expect("Failed to deserialize");
+        assert_eq!(config.scope_groups().mode(), ScopeMode::Off);
+
+        let toml_content = r#"
+[scope-groups]
+mode = "headings"
+order = ["core"]
+
+[scope-groups.names]
+core = "Core library"
+"#;
+        let config: ChangeLogConfig = toml::from_str(toml_content).expect("Failed to deserialize");
+        assert_eq!(config.scope_groups().mode(), ScopeMode::Headings);
+        assert_eq!(config.scope_groups().display_name("core"), "Core library");
+    }
+
+    #[test]
+    fn test_gitmoji_from_toml() {
+        let config: ChangeLogConfig = toml::from_str("").expect("Failed to deserialize");
+        assert!(!config.gitmoji().enabled());
+        assert_eq!(config.gitmoji().render(), EmojiRender::Keep);
+
+        let toml_content = r#"
+[gitmoji]
+enabled = true
+render = "strip"
+
+[gitmoji.groups]
+":rocket:" = "Continuous Integration"
+"#;
+
+        let config: ChangeLogConfig = toml::from_str(toml_content).expect("Failed to deserialize");
+        assert!(config.gitmoji().enabled());
+        assert_eq!(config.gitmoji().render(), EmojiRender::Strip);
+
+        let result: Result<ChangeLogConfig, _> = toml::from_str("[gitmoji]\nrender = \"hide\"");
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_invalid_toml_fields() {
         // Test that unknown fields are rejected due to serde(deny_unknown_fields)
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+use crate::change_log_config::glob::glob_match;
+
+/// AuthorFilter selects commits by the name or email of their author.
+///
+/// Patterns are simple globs (`*` and `?`) matched case-insensitively against
+/// both the author name and the author email. A commit is excluded when an
+/// `exclude` pattern matches, or when `include` patterns are set and none of
+/// them match. Excluded commits are dropped unless a `group` is set, in which
+/// case they are routed to that group instead.
+///
+/// # Example
+///
+/// ```toml
+/// [author-filter]
+/// exclude = ["*[bot]", "*[bot]@users.noreply.github.com"]
+/// group = "Dependencies"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+#[serde(rename_all = "kebab-case")]
+pub struct AuthorFilter {
+    /// Glob patterns for authors to keep. When empty every author is kept.
+    include: Vec<String>,
+    /// Glob patterns for authors whose commits are excluded.
+    exclude: Vec<String>,
+    /// Group to receive excluded commits instead of dropping them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+}
+
+impl AuthorFilter {
+    /// Returns the glob patterns for authors to keep.
+    pub fn include(&self) -> &[String] {
+        &self.include
+    }
+
+    /// Returns the glob patterns for authors to exclude.
+    pub fn exclude(&self) -> &[String] {
+        &self.exclude
+    }
+
+    /// Returns the group that receives excluded commits, if any.
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    /// Adds a glob pattern for authors to keep.
+    pub fn add_include(&mut self, pattern: &str) -> &mut Self {
+        self.include.push(pattern.to_string());
+        self
+    }
+
+    /// Adds a glob pattern for authors to exclude.
+    pub fn add_exclude(&mut self, pattern: &str) -> &mut Self {
+        self.exclude.push(pattern.to_string());
+        self
+    }
+
+    /// Sets the group that receives excluded commits. With `None` excluded
+    /// commits are dropped.
+    pub fn set_group(&mut self, group: Option<&str>) -> &mut Self {
+        self.group = group.map(|g| g.to_string());
+        self
+    }
+
+    /// Test to determine if a commit by the author should be excluded.
+    pub(crate) fn is_excluded(&self, name: &str, email: &str) -> bool {
+        let name = name.to_lowercase();
+        let email = email.to_lowercase();
+        let matches = |pattern: &String| {
+            let pattern = pattern.to_lowercase();
+            glob_match(&pattern, &name) || glob_match(&pattern, &email)
+        };
+
+        if self.exclude.iter().any(matches) {
+            return true;
+        }
+
+        !self.include.is_empty() && !self.include.iter().any(matches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AuthorFilter;
+
+    #[test]
+    fn test_default_excludes_nobody() {
+        let filter = AuthorFilter::default();
+        assert!(!filter.is_excluded("renovate[bot]", "bot@renovateapp.com"));
+        assert!(!filter.is_excluded("Jane Doe", "jane@example.com"));
+    }
+
+    #[test]
+    fn test_exclude_by_name_or_email() {
+        let mut filter = AuthorFilter::default();
+        filter.add_exclude("*[bot]").add_exclude("release-bot@*");
+
+        assert!(filter.is_excluded("dependabot[bot]", "support@github.com"));
+        assert!(filter.is_excluded("Release Bot", "release-bot@example.com"));
+        assert!(filter.is_excluded("Renovate[Bot]", "bot@renovateapp.com"));
+        assert!(!filter.is_excluded("Jane Doe", "jane@example.com"));
+    }
+
+    #[test]
+    fn test_include_restricts_authors() {
+        let mut filter = AuthorFilter::default();
+        filter.add_include("*@example.com").add_exclude("ci@*");
+
+        assert!(!filter.is_excluded("Jane Doe", "jane@example.com"));
+        assert!(filter.is_excluded("Someone", "someone@elsewhere.org"));
+        assert!(filter.is_excluded("CI", "ci@example.com"));
+    }
+
+    #[test]
+    fn test_deserialize_from_toml() {
+        let filter: AuthorFilter = toml::from_str(
+            r#"
+            exclude = ["*[bot]"]
+            group = "Dependencies"
+            "#,
+        )
+        .expect("deserialize author filter");
+
+        assert!(filter.include().is_empty());
+        assert_eq!(filter.exclude(), ["*[bot]".to_string()]);
+        assert_eq!(filter.group(), Some("Dependencies"));
+    }
+}
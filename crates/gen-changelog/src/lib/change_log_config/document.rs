@@ -0,0 +1,432 @@
+//! Editing of a configuration file in place
+//!
+//! [`ChangeLogConfig::save`] writes a whole configuration with the default
+//! comments. A [`ConfigDocument`] instead edits the file a user has written,
+//! changing only the values that differ so that their comments, ordering and
+//! formatting are kept.
+
+use std::path::{Path, PathBuf};
+
+use toml_edit::{DocumentMut, Item, Key, TableLike};
+
+use crate::{ChangeLogConfig, Error, Severity};
+
+/// ConfigDocument edits a configuration file, keeping its comments and layout.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use gen_changelog::ConfigDocument;
+///
+/// # fn main() -> Result<(), gen_changelog::Error> {
+/// let mut document = ConfigDocument::open("gen-changelog.toml")?;
+/// document.set("first-parent", "true")?;
+///
+/// let mut config = document.config()?;
+/// config.publish_group("Documentation");
+/// document.update(&config)?;
+///
+/// document.save()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConfigDocument {
+    path: PathBuf,
+    document: DocumentMut,
+}
+
+impl ConfigDocument {
+    /// Opens the configuration file, or starts an empty document when the
+    /// file does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not valid TOML.
+    pub fn open<P: Into<PathBuf>>(path: P) -> Result<Self, Error> {
+        let path = path.into();
+        let document = if path.is_file() {
+            std::fs::read_to_string(&path)?.parse::<DocumentMut>()?
+        } else {
+            DocumentMut::new()
+        };
+
+        Ok(ConfigDocument { path, document })
+    }
+
+    /// Returns the path of the configuration file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the configuration described by the document, with defaults for
+    /// the values it does not set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document is not a valid configuration.
+    pub fn config(&self) -> Result<ChangeLogConfig, Error> {
+        Ok(toml::from_str(&self.document.to_string())?)
+    }
+
+    /// Returns the value of a key in the configuration, as TOML.
+    ///
+    /// The key is a dotted path such as `lint.max-subject-length` or
+    /// `groups.Added.publish`. Values the document does not set are taken from
+    /// the defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownConfigKey`] if the key is not part of the
+    /// configuration, or an error if the document is not a valid
+    /// configuration.
+    pub fn get(&self, key: &str) -> Result<String, Error> {
+        let effective = to_document(&self.config()?)?;
+        let mut item = effective.as_item();
+        for part in parse_key(key)? {
+            item = item
+                .as_table_like()
+                .and_then(|t| t.get(part.get()))
+                .ok_or_else(|| Error::UnknownConfigKey(key.to_string()))?;
+        }
+
+        let value = match item.clone().into_value() {
+            Ok(value) => value.decorated("", "").to_string(),
+            Err(item) => item.to_string(),
+        };
+        Ok(value)
+    }
+
+    /// Sets the value of a key in the document.
+    ///
+    /// The value is parsed as TOML, and taken as a string when it is not valid
+    /// TOML. The comments around an existing value are kept. A table the
+    /// document does not set is written whole from the current configuration,
+    /// as a partial table would replace the defaults for the missing keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the document unchanged, if the key or value
+    /// does not make a valid configuration or the configuration has errors
+    /// reported by [`ChangeLogConfig::validate`].
+    pub fn set(&mut self, key: &str, value: &str) -> Result<&mut Self, Error> {
+        let value = value
+            .parse::<toml_edit::Value>()
+            .unwrap_or_else(|_| value.into());
+        let parts = parse_key(key)?;
+        let Some((last, tables)) = parts.split_last() else {
+            return Err(Error::UnknownConfigKey(key.to_string()));
+        };
+
+        let effective = to_document(&self.config()?)?;
+        let mut current = Some(effective.as_table() as &dyn TableLike);
+        let mut document = self.document.clone();
+        let mut table: &mut dyn TableLike = document.as_table_mut();
+        for part in tables {
+            let current_item = current.and_then(|t| t.get(part.get()));
+            current = current_item.and_then(Item::as_table_like);
+            if !table.contains_key(part.get()) {
+                let mut item = current_item.cloned().unwrap_or_else(toml_edit::table);
+                clear_positions(&mut item);
+                table.insert(part.get(), item);
+            }
+            table = table
+                .get_mut(part.get())
+                .and_then(Item::as_table_like_mut)
+                .ok_or_else(|| Error::UnknownConfigKey(key.to_string()))?;
+        }
+        replace(table, last.get(), Item::Value(value));
+
+        let config = toml::from_str::<ChangeLogConfig>(&document.to_string())?;
+        let errors = config
+            .validate()
+            .into_iter()
+            .filter(|d| d.severity() == Severity::Error)
+            .inspect(|d| log::error!("{d}"))
+            .count();
+        if errors > 0 {
+            return Err(Error::ConfigInvalid(errors));
+        }
+        self.document = document;
+        Ok(self)
+    }
+
+    /// Updates the document to describe the configuration.
+    ///
+    /// Only the values that differ from the configuration described by the
+    /// document are written, so values left to their defaults stay unset.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document is not a valid configuration or the
+    /// configuration cannot be serialized.
+    pub fn update(&mut self, config: &ChangeLogConfig) -> Result<&mut Self, Error> {
+        let current = to_document(&self.config()?)?;
+        let updated = to_document(config)?;
+        apply_changes(
+            self.document.as_table_mut(),
+            current.as_table(),
+            updated.as_table(),
+        );
+        Ok(self)
+    }
+
+    /// Writes the document to the configuration file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self) -> Result<(), Error> {
+        std::fs::write(&self.path, self.document.to_string())?;
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for ConfigDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.document)
+    }
+}
+
+fn to_document(config: &ChangeLogConfig) -> Result<DocumentMut, Error> {
+    Ok(toml::to_string(config)?.parse::<DocumentMut>()?)
+}
+
+fn parse_key(key: &str) -> Result<Vec<Key>, Error> {
+    Key::parse(key).map_err(|_| Error::UnknownConfigKey(key.to_string()))
+}
+
+/// Applies the differences between the current and updated tables to the
+/// target table.
+///
+/// A table the target does not set is written whole, as a partial table would
+/// replace the defaults for the missing keys.
+fn apply_changes(target: &mut dyn TableLike, current: &dyn TableLike, updated: &dyn TableLike) {
+    for (key, item) in updated.iter() {
+        let current_item = current.get(key);
+        if let (Some(current_table), Some(updated_table), Some(target_table)) = (
+            current_item.and_then(Item::as_table_like),
+            item.as_table_like(),
+            target.get_mut(key).and_then(Item::as_table_like_mut),
+        ) {
+            apply_changes(target_table, current_table, updated_table);
+        } else if current_item.is_none_or(|c| !same_value(c, item)) {
+            replace(target, key, item.clone());
+        }
+    }
+
+    let removed = current
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !updated.contains_key(key))
+        .collect::<Vec<_>>();
+    for key in removed {
+        target.remove(&key);
+    }
+}
+
+/// Sets the item, keeping the comments around an existing key and value
+fn replace(table: &mut dyn TableLike, key: &str, mut item: Item) {
+    clear_positions(&mut item);
+    let Some(existing) = table.get_mut(key) else {
+        table.insert(key, item);
+        return;
+    };
+    match (&*existing, &mut item) {
+        (Item::Value(existing), Item::Value(value)) => {
+            *value.decor_mut() = existing.decor().clone();
+        }
+        (Item::Table(existing), Item::Table(value)) => {
+            *value.decor_mut() = existing.decor().clone();
+            value.set_position(existing.position());
+        }
+        _ => {}
+    }
+    *existing = item;
+}
+
+/// Positions of tables taken from another document would interleave them with
+/// the tables of the target document
+fn clear_positions(item: &mut Item) {
+    match item {
+        Item::Table(table) => {
+            table.set_position(None);
+            table.iter_mut().for_each(|(_, item)| clear_positions(item));
+        }
+        Item::ArrayOfTables(array) => array.iter_mut().for_each(|table| {
+            table.set_position(None);
+            table.iter_mut().for_each(|(_, item)| clear_positions(item));
+        }),
+        _ => {}
+    }
+}
+
+fn same_value(a: &Item, b: &Item) -> bool {
+    match (a.clone().into_value(), b.clone().into_value()) {
+        (Ok(a), Ok(b)) => a.decorated("", "").to_string() == b.decorated("", "").to_string(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const CONFIG: &str = r#"# Our changelog settings
+cherry-pick-policy = "latest"
+
+# Keep merges out
+include-merge-commits = false # see the release guide
+
+[lint]
+# Long subjects get truncated in the changelog
+max-subject-length = 72
+"#;
+
+    fn fixture_document(content: &str) -> (TempDir, ConfigDocument) {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let path = temp_dir.path().join("gen-changelog.toml");
+        std::fs::write(&path, content).unwrap();
+        let document = ConfigDocument::open(path).expect("open");
+        (temp_dir, document)
+    }
+
+    #[test]
+    fn test_get_reads_document_and_defaults() {
+        let (_td, document) = fixture_document(CONFIG);
+
+        assert_eq!(document.get("lint.max-subject-length").unwrap(), "72");
+        assert_eq!(document.get("first-parent").unwrap(), "false");
+        assert_eq!(document.get("groups.Added.publish").unwrap(), "true");
+        assert!(matches!(
+            document.get("lint.no-such-key"),
+            Err(Error::UnknownConfigKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_set_keeps_comments() {
+        let (_td, mut document) = fixture_document(CONFIG);
+
+        document.set("include-merge-commits", "true").unwrap();
+        document.set("lint.max-subject-length", "50").unwrap();
+        document.set("unknown-group", "Other").unwrap();
+        document.save().unwrap();
+
+        let saved = std::fs::read_to_string(document.path()).unwrap();
+        assert!(saved.starts_with("# Our changelog settings\n"));
+        assert!(saved.contains("# Keep merges out\ninclude-merge-commits = true # see the"));
+        assert!(
+            saved.contains(
+                "# Long subjects get truncated in the changelog\nmax-subject-length = 50\n"
+            )
+        );
+        assert!(saved.contains("unknown-group = \"Other\""));
+        assert_eq!(document.config().unwrap().unknown_group(), "Other");
+    }
+
+    #[rstest::rstest]
+    #[case("no-such-key", "1")]
+    #[case("first-parent", "\"yes\"")]
+    #[case("lint.max-subject-length", "-1")]
+    fn test_set_rejects_invalid_config(#[case] key: &str, #[case] value: &str) {
+        let (_td, mut document) = fixture_document(CONFIG);
+
+        assert!(document.set(key, value).is_err());
+        assert_eq!(document.to_string(), CONFIG);
+    }
+
+    #[test]
+    fn test_update_writes_changed_values_only() {
+        let (_td, mut document) = fixture_document(CONFIG);
+
+        let mut config = document.config().unwrap();
+        config.set_first_parent(true);
+        config.publish_group("Documentation");
+        document.update(&config).unwrap();
+
+        let updated = document.to_string();
+        assert!(updated.starts_with(
+            "# Our changelog settings\ncherry-pick-policy = \"latest\"\n\n# Keep merges out\n"
+        ));
+        assert!(updated.contains("# see the release guide\nfirst-parent = true\n"));
+        assert!(updated.contains("# Long subjects get truncated in the changelog\n"));
+        assert!(updated.contains("Documentation = 6"));
+        // The groups are written whole, as the document did not set them
+        assert!(updated.contains("[groups.Fixed]"));
+        assert!(!updated.contains("type-aliases"));
+
+        let updated = document.config().unwrap();
+        assert!(updated.first_parent());
+        assert_eq!(
+            updated.headings().values().last().map(String::as_str),
+            Some("Documentation")
+        );
+
+        // A second update only changes the values in place
+        let mut config = updated;
+        config.unpublish_group("Documentation");
+        let before = document.to_string();
+        document.update(&config).unwrap();
+        assert!(!document.to_string().contains("Documentation = 6"));
+        assert_eq!(
+            document.to_string().lines().count(),
+            before.lines().count() - 1
+        );
+    }
+
+    #[test]
+    fn test_set_writes_missing_table_whole() {
+        let (_td, mut document) = fixture_document(CONFIG);
+
+        document.set("headings.Documentation", "6").unwrap();
+        document.set("groups.Added.publish", "false").unwrap();
+
+        let config = document.config().unwrap();
+        assert!(config.validate().is_empty(), "{document}");
+        assert_eq!(config.headings().len(), 6);
+        assert_eq!(
+            config.headings().values().last().map(String::as_str),
+            Some("Documentation")
+        );
+        assert_eq!(document.get("groups.Added.name").unwrap(), "\"Added\"");
+        assert_eq!(document.get("groups.Added.publish").unwrap(), "false");
+        assert_eq!(document.get("groups.Fixed.publish").unwrap(), "true");
+        assert_eq!(
+            config.groups_mapping().get("feat").map(String::as_str),
+            Some("Added")
+        );
+        // The comments of the document are kept
+        assert!(document.to_string().starts_with(CONFIG));
+    }
+
+    #[test]
+    fn test_set_rejects_config_with_errors() {
+        let (_td, mut document) = fixture_document(CONFIG);
+
+        assert!(matches!(
+            document.set("headings.Features", "7"),
+            Err(Error::ConfigInvalid(1))
+        ));
+        assert_eq!(document.to_string(), CONFIG);
+    }
+
+    #[test]
+    fn test_open_missing_file() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let mut document =
+            ConfigDocument::open(temp_dir.path().join("gen-changelog.toml")).expect("open");
+
+        assert_eq!(document.to_string(), "");
+        document
+            .set("display-sections", "{ variant = \"one\" }")
+            .unwrap();
+        document.save().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(document.path()).unwrap(),
+            "display-sections = { variant = \"one\" }\n"
+        );
+    }
+}
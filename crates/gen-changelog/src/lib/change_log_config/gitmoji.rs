@@ -0,0 +1,283 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+/// The official gitmoji list (<https://gitmoji.dev>) with the default group
+/// for each emoji as `(emoji, shortcode, group)`.
+const DEFAULT_GITMOJIS: [(&str, &str, &str); 74] = [
+    ("🎨", ":art:", "Changed"),
+    ("⚡️", ":zap:", "Changed"),
+    ("🔥", ":fire:", "Removed"),
+    ("🐛", ":bug:", "Fixed"),
+    ("🚑️", ":ambulance:", "Fixed"),
+    ("✨", ":sparkles:", "Added"),
+    ("📝", ":memo:", "Documentation"),
+    ("🚀", ":rocket:", "Build"),
+    ("💄", ":lipstick:", "Changed"),
+    ("🎉", ":tada:", "Added"),
+    ("✅", ":white_check_mark:", "Testing"),
+    ("🔒️", ":lock:", "Security"),
+    ("🔐", ":closed_lock_with_key:", "Security"),
+    ("🔖", ":bookmark:", "Chore"),
+    ("🚨", ":rotating_light:", "Chore"),
+    ("🚧", ":construction:", "Miscellaneous"),
+    ("💚", ":green_heart:", "Continuous Integration"),
+    ("⬇️", ":arrow_down:", "Security"),
+    ("⬆️", ":arrow_up:", "Security"),
+    ("📌", ":pushpin:", "Security"),
+    ("👷", ":construction_worker:", "Continuous Integration"),
+    ("📈", ":chart_with_upwards_trend:", "Added"),
+    ("♻️", ":recycle:", "Changed"),
+    ("➕", ":heavy_plus_sign:", "Security"),
+    ("➖", ":heavy_minus_sign:", "Security"),
+    ("🔧", ":wrench:", "Chore"),
+    ("🔨", ":hammer:", "Build"),
+    ("🌐", ":globe_with_meridians:", "Added"),
+    ("✏️", ":pencil2:", "Fixed"),
+    ("💩", ":poop:", "Miscellaneous"),
+    ("⏪️", ":rewind:", "Reverted"),
+    ("🔀", ":twisted_rightwards_arrows:", "Miscellaneous"),
+    ("📦️", ":package:", "Build"),
+    ("👽️", ":alien:", "Changed"),
+    ("🚚", ":truck:", "Changed"),
+    ("📄", ":page_facing_up:", "Chore"),
+    ("💥", ":boom:", "Changed"),
+    ("🍱", ":bento:", "Added"),
+    ("♿️", ":wheelchair:", "Changed"),
+    ("💡", ":bulb:", "Documentation"),
+    ("🍻", ":beers:", "Miscellaneous"),
+    ("💬", ":speech_balloon:", "Changed"),
+    ("🗃️", ":card_file_box:", "Changed"),
+    ("🔊", ":loud_sound:", "Added"),
+    ("🔇", ":mute:", "Removed"),
+    ("👥", ":busts_in_silhouette:", "Chore"),
+    ("🚸", ":children_crossing:", "Changed"),
+    ("🏗️", ":building_construction:", "Changed"),
+    ("📱", ":iphone:", "Changed"),
+    ("🤡", ":clown_face:", "Testing"),
+    ("🥚", ":egg:", "Added"),
+    ("🙈", ":see_no_evil:", "Chore"),
+    ("📸", ":camera_flash:", "Testing"),
+    ("⚗️", ":alembic:", "Miscellaneous"),
+    ("🔍️", ":mag:", "Changed"),
+    ("🏷️", ":label:", "Changed"),
+    ("🌱", ":seedling:", "Added"),
+    ("🚩", ":triangular_flag_on_post:", "Added"),
+    ("🥅", ":goal_net:", "Fixed"),
+    ("💫", ":dizzy:", "Changed"),
+    ("🗑️", ":wastebasket:", "Deprecated"),
+    ("🛂", ":passport_control:", "Security"),
+    ("🩹", ":adhesive_bandage:", "Fixed"),
+    ("🧐", ":monocle_face:", "Miscellaneous"),
+    ("⚰️", ":coffin:", "Removed"),
+    ("🧪", ":test_tube:", "Testing"),
+    ("👔", ":necktie:", "Changed"),
+    ("🩺", ":stethoscope:", "Added"),
+    ("🧱", ":bricks:", "Build"),
+    ("🧑‍💻", ":technologist:", "Changed"),
+    ("💸", ":money_with_wings:", "Chore"),
+    ("🧵", ":thread:", "Changed"),
+    ("🦺", ":safety_vest:", "Added"),
+    ("✈️", ":airplane:", "Added"),
+];
+
+/// How emojis at the start of a commit summary are written in the changelog.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EmojiRender {
+    /// Keep the emoji in the changelog entry
+    #[default]
+    Keep,
+    /// Remove the emoji from the changelog entry
+    Strip,
+}
+
+/// Gitmoji configures classification of commits by a leading gitmoji.
+///
+/// When enabled, a commit summary that is not a conventional commit but starts
+/// with an emoji (e.g. `✨ add search`) or a shortcode (e.g. `:bug: fix
+/// crash`) is listed in the group mapped to that gitmoji. The official gitmoji
+/// list is mapped by default; entries in `groups` add to or replace those
+/// mappings. Mapping an official emoji also maps its shortcode, and the
+/// reverse, unless the partner is mapped as well.
+///
+/// # Example
+///
+/// ```toml
+/// [gitmoji]
+/// enabled = true
+/// render = "strip"
+///
+/// [gitmoji.groups]
+/// "🚀" = "Continuous Integration"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+#[serde(rename_all = "kebab-case")]
+pub struct Gitmoji {
+    /// Classify commits by their leading gitmoji.
+    enabled: bool,
+    /// Keep or strip the emoji in changelog entries.
+    render: EmojiRender,
+    /// Emojis and shortcodes mapped to groups, in addition to the defaults.
+    groups: BTreeMap<String, String>,
+}
+
+impl Gitmoji {
+    /// Returns whether commits are classified by their leading gitmoji.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns how emojis are written in changelog entries.
+    pub fn render(&self) -> EmojiRender {
+        self.render
+    }
+
+    /// Sets whether commits are classified by their leading gitmoji.
+    pub fn set_enabled(&mut self, value: bool) -> &mut Self {
+        self.enabled = value;
+        self
+    }
+
+    /// Sets how emojis are written in changelog entries.
+    pub fn set_render(&mut self, render: EmojiRender) -> &mut Self {
+        self.render = render;
+        self
+    }
+
+    /// Maps an emoji or `:shortcode:` to a group, replacing any default
+    /// mapping.
+    ///
+    /// The shortcode of an official emoji, or the emoji of an official
+    /// shortcode, is mapped to the same group unless it is mapped itself.
+    pub fn map_to_group(&mut self, gitmoji: &str, group: &str) -> &mut Self {
+        self.groups.insert(gitmoji.to_string(), group.to_string());
+        self
+    }
+
+    /// Table of emojis and shortcodes to groups combining the official
+    /// gitmoji list with the configured mappings.
+    ///
+    /// Emoji keys are normalised with [`normalise_gitmoji`].
+    pub(crate) fn group_table(&self) -> HashMap<String, String> {
+        let mut table = HashMap::new();
+        for (emoji, code, group) in DEFAULT_GITMOJIS {
+            table.insert(normalise_gitmoji(emoji), group.to_string());
+            table.insert(code.to_string(), group.to_string());
+        }
+        // Map the partner of each configured key first so that a partner
+        // configured in its own right keeps its own group
+        for (gitmoji, group) in &self.groups {
+            if let Some(partner) = partner(gitmoji) {
+                table.insert(partner, group.clone());
+            }
+        }
+        for (gitmoji, group) in &self.groups {
+            table.insert(normalise_gitmoji(gitmoji), group.clone());
+        }
+        table
+    }
+}
+
+/// The shortcode of an official emoji or the normalised emoji of an official
+/// shortcode.
+fn partner(gitmoji: &str) -> Option<String> {
+    let gitmoji = normalise_gitmoji(gitmoji);
+    DEFAULT_GITMOJIS.iter().find_map(|(emoji, code, _)| {
+        if normalise_gitmoji(emoji) == gitmoji {
+            Some(code.to_string())
+        } else if *code == gitmoji {
+            Some(normalise_gitmoji(emoji))
+        } else {
+            None
+        }
+    })
+}
+
+/// Remove the emoji variation selector so that emojis match whether or not it
+/// was typed.
+pub(crate) fn normalise_gitmoji(gitmoji: &str) -> String {
+    gitmoji.replace('\u{fe0f}', "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_table_maps_emoji_and_shortcode() {
+        let table = Gitmoji::default().group_table();
+
+        assert_eq!(table.get("✨"), Some(&"Added".to_string()));
+        assert_eq!(table.get(":sparkles:"), Some(&"Added".to_string()));
+        assert_eq!(table.get(":bug:"), Some(&"Fixed".to_string()));
+        // Stored without the variation selector
+        assert_eq!(table.get("⚡"), Some(&"Changed".to_string()));
+        assert_eq!(
+            table.get(&normalise_gitmoji("⚡️")),
+            Some(&"Changed".to_string())
+        );
+    }
+
+    #[test]
+    fn test_configured_groups_override_defaults() {
+        let mut gitmoji = Gitmoji::default();
+        gitmoji
+            .map_to_group("🚀", "Continuous Integration")
+            .map_to_group(":memo:", "Docs")
+            .map_to_group("⚡️", "Performance")
+            .map_to_group("🐛", "Bugs")
+            .map_to_group(":bug:", "Fixed")
+            .map_to_group("🦀", "Rust");
+        let table = gitmoji.group_table();
+
+        // The partner of a mapped emoji or shortcode follows it
+        assert_eq!(table.get("🚀"), Some(&"Continuous Integration".to_string()));
+        assert_eq!(
+            table.get(":rocket:"),
+            Some(&"Continuous Integration".to_string())
+        );
+        assert_eq!(table.get(":memo:"), Some(&"Docs".to_string()));
+        assert_eq!(table.get("📝"), Some(&"Docs".to_string()));
+        assert_eq!(table.get(":zap:"), Some(&"Performance".to_string()));
+        // Unless it is mapped itself
+        assert_eq!(table.get("🐛"), Some(&"Bugs".to_string()));
+        assert_eq!(table.get(":bug:"), Some(&"Fixed".to_string()));
+        // Emojis outside the official list have no partner
+        assert_eq!(table.get("🦀"), Some(&"Rust".to_string()));
+    }
+
+    #[test]
+    fn test_default_gitmojis_map_to_default_groups() {
+        for (emoji, _, group) in DEFAULT_GITMOJIS {
+            assert!(
+                super::super::DEFAULT_GROUPS
+                    .iter()
+                    .any(|(name, _, _)| *name == group),
+                "{emoji} maps to `{group}` which is not a default group"
+            );
+        }
+    }
+
+    #[test]
+    fn test_deserialize_from_toml() {
+        let gitmoji: Gitmoji = toml::from_str(
+            r#"
+            enabled = true
+            render = "strip"
+
+            [groups]
+            ":rocket:" = "Continuous Integration"
+            "#,
+        )
+        .expect("deserialize gitmoji");
+
+        assert!(gitmoji.enabled());
+        assert_eq!(gitmoji.render(), EmojiRender::Strip);
+        assert_eq!(
+            gitmoji.group_table().get(":rocket:"),
+            Some(&"Continuous Integration".to_string())
+        );
+    }
+}
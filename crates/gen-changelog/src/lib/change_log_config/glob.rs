@@ -0,0 +1,59 @@
+/// Match `text` against a simple glob `pattern`.
+///
+/// Supports `*` (any run of characters, including none) and `?` (exactly one
+/// character). All other characters match themselves.
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` seen and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last `*` absorb one more character and retry
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, t));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::glob_match;
+
+    #[rstest]
+    #[case("renovate[bot]", "renovate[bot]", true)]
+    #[case("*[bot]", "dependabot[bot]", true)]
+    #[case("*[bot]", "dependabot", false)]
+    #[case(
+        "*@users.noreply.github.com",
+        "29139614+renovate[bot]@users.noreply.github.com",
+        true
+    )]
+    #[case("release-?ot", "release-bot", true)]
+    #[case("release-?ot", "release-boot", false)]
+    #[case("*", "", true)]
+    #[case("", "", true)]
+    #[case("", "a", false)]
+    #[case("a*b*c", "axxbyyc", true)]
+    #[case("a*b*c", "axxbyy", false)]
+    #[case("a*", "a*x", true)]
+    fn test_glob_match(#[case] pattern: &str, #[case] text: &str, #[case] expected: bool) {
+        assert_eq!(glob_match(pattern, text), expected);
+    }
+}
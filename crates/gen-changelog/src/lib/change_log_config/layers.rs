@@ -0,0 +1,613 @@
+//! Configuration assembled from several files
+//!
+//! The configuration is read from these sources, each overriding the values
+//! set by the sources before it:
+//! 1. the built-in defaults
+//! 2. the user file, `$XDG_CONFIG_HOME/gen-changelog/config.toml` or
+//!    `~/.config/gen-changelog/config.toml`
+//! 3. the `[workspace.metadata.gen-changelog]` and
+//!    `[package.metadata.gen-changelog]` tables of the repository `Cargo.toml`
+//! 4. `gen-changelog.toml` at the root of the repository
+//! 5. the `[package.metadata.gen-changelog]` table of the package `Cargo.toml`
+//! 6. `gen-changelog.toml` at the root of the package the changelog is
+//!    generated for
+//!
+//! The metadata tables take the same settings as `gen-changelog.toml`, for
+//! crates that would rather not add another file.
+//!
+//! Command line options are applied over the assembled configuration.
+//!
+//! A value set in a file replaces the value from the sources before it, and
+//! tables are merged key by key. The `headings` and `display-sections` tables
+//! and each group in `groups` are replaced whole, so a file lists every
+//! heading it publishes and every setting of a group it redefines.
+
+use std::{
+    fmt::Display,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use toml::{Table, Value};
+
+use crate::{ChangeLogConfig, Error};
+
+/// Name of the configuration file in the repository and package roots
+const CONFIG_FILE: &str = "gen-changelog.toml";
+
+/// Name of the Cargo manifest in the repository and package roots
+const CARGO_MANIFEST: &str = "Cargo.toml";
+
+/// Key of the configuration in the metadata tables of the Cargo manifest
+const METADATA_KEY: &str = "gen-changelog";
+
+/// Path of the user configuration file relative to the user config directory
+const USER_CONFIG_FILE: &str = "gen-changelog/config.toml";
+
+/// Source of a configuration value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Built-in default
+    Default,
+    /// File in the user config directory
+    User(PathBuf),
+    /// `[workspace.metadata.gen-changelog]` table of a Cargo manifest
+    WorkspaceMetadata(PathBuf),
+    /// `[package.metadata.gen-changelog]` table of a Cargo manifest
+    PackageMetadata(PathBuf),
+    /// File at the root of the repository
+    Repository(PathBuf),
+    /// File at the root of the package
+    Package(PathBuf),
+    /// File named explicitly, used instead of the discovered files
+    File(PathBuf),
+    /// Command line option
+    CommandLine,
+}
+
+impl Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::User(path) => write!(f, "user file `{}`", path.display()),
+            ConfigSource::WorkspaceMetadata(path) => write!(
+                f,
+                "[workspace.metadata.{METADATA_KEY}] in `{}`",
+                path.display()
+            ),
+            ConfigSource::PackageMetadata(path) => write!(
+                f,
+                "[package.metadata.{METADATA_KEY}] in `{}`",
+                path.display()
+            ),
+            ConfigSource::Repository(path) => write!(f, "repository file `{}`", path.display()),
+            ConfigSource::Package(path) => write!(f, "package file `{}`", path.display()),
+            ConfigSource::File(path) => write!(f, "file `{}`", path.display()),
+            ConfigSource::CommandLine => write!(f, "command line"),
+        }
+    }
+}
+
+/// ConfigLayers assembles the configuration from the files setting it.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::path::Path;
+///
+/// use gen_changelog::ConfigLayers;
+///
+/// # fn main() -> Result<(), gen_changelog::Error> {
+/// let user_dir = ConfigLayers::user_config_dir();
+/// let layers = ConfigLayers::discover(
+///     user_dir.as_deref(),
+///     Path::new("."),
+///     Some(Path::new("crates/foo")),
+/// )?;
+/// let config = layers.config()?;
+///
+/// for (key, value, source) in layers.origins(&config)? {
+///     println!("{key} = {value}  # {source}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLayers {
+    layers: Vec<(ConfigSource, Table)>,
+}
+
+impl ConfigLayers {
+    /// Creates layers holding only the built-in defaults.
+    pub fn new() -> Self {
+        ConfigLayers::default()
+    }
+
+    /// Returns the user config directory, following the XDG base directory
+    /// convention: `$XDG_CONFIG_HOME` when set to an absolute path, or
+    /// `~/.config`.
+    pub fn user_config_dir() -> Option<PathBuf> {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    }
+
+    /// Finds the user, repository and package configuration files and the
+    /// configuration in the Cargo manifests.
+    ///
+    /// Files that do not exist are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_config_dir` - The user config directory, usually
+    ///   [`ConfigLayers::user_config_dir`], or `None` to skip the user file
+    /// * `repository_root` - The root of the repository
+    /// * `package_root` - The root of the package, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be read or parsed.
+    pub fn discover(
+        user_config_dir: Option<&Path>,
+        repository_root: &Path,
+        package_root: Option<&Path>,
+    ) -> Result<Self, Error> {
+        let mut layers = ConfigLayers::new();
+        if let Some(dir) = user_config_dir {
+            layers.add_file(ConfigSource::User(dir.join(USER_CONFIG_FILE)))?;
+        }
+        let manifest = repository_root.join(CARGO_MANIFEST);
+        layers
+            .add_file(ConfigSource::WorkspaceMetadata(manifest.clone()))?
+            .add_file(ConfigSource::PackageMetadata(manifest))?
+            .add_file(ConfigSource::Repository(repository_root.join(CONFIG_FILE)))?;
+        // The root package of the workspace has been read with the repository
+        if let Some(root) = package_root.filter(|root| *root != repository_root) {
+            layers
+                .add_file(ConfigSource::PackageMetadata(root.join(CARGO_MANIFEST)))?
+                .add_file(ConfigSource::Package(root.join(CONFIG_FILE)))?;
+        }
+
+        Ok(layers)
+    }
+
+    /// Adds the file named by the source over the current layers, unless it
+    /// does not exist.
+    ///
+    /// For the metadata sources the file is a Cargo manifest, skipped when it
+    /// has no `gen-changelog` metadata table or is not valid TOML.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn add_file(&mut self, source: ConfigSource) -> Result<&mut Self, Error> {
+        let path = match &source {
+            ConfigSource::User(path)
+            | ConfigSource::WorkspaceMetadata(path)
+            | ConfigSource::PackageMetadata(path)
+            | ConfigSource::Repository(path)
+            | ConfigSource::Package(path)
+            | ConfigSource::File(path) => path,
+            ConfigSource::Default | ConfigSource::CommandLine => return Ok(self),
+        };
+        if !path.is_file() {
+            log::debug!("no configuration file at `{}`", path.display());
+            return Ok(self);
+        }
+
+        let table = match &source {
+            ConfigSource::WorkspaceMetadata(_) => metadata_table(path, "workspace")?,
+            ConfigSource::PackageMetadata(_) => metadata_table(path, "package")?,
+            _ => Some(read_to_string(path)?.parse::<Table>()?),
+        };
+        let Some(table) = table else {
+            log::debug!("no configuration in `{}`", path.display());
+            return Ok(self);
+        };
+
+        log::debug!("configuration read from {source}");
+        self.layers.push((source, table));
+        Ok(self)
+    }
+
+    /// Returns the sources of the layers, lowest precedence first.
+    pub fn sources(&self) -> Vec<&ConfigSource> {
+        self.layers.iter().map(|(source, _)| source).collect()
+    }
+
+    /// Returns the configuration assembled from the layers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the assembled settings are not a valid
+    /// configuration.
+    pub fn config(&self) -> Result<ChangeLogConfig, Error> {
+        // The defaults are the first layer, so a file only replaces the
+        // groups it defines
+        let mut merged = Table::try_from(ChangeLogConfig::default())?;
+        for (_, table) in &self.layers {
+            merge(&mut merged, table, &[]);
+        }
+
+        Ok(Value::Table(merged).try_into()?)
+    }
+
+    /// Returns each value of the configuration as TOML, with its dotted key and
+    /// its source.
+    ///
+    /// Values of the configuration that differ from the assembled
+    /// configuration, such as those changed by command line options, are
+    /// reported as coming from the command line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the assembled settings are not a valid
+    /// configuration.
+    pub fn origins(
+        &self,
+        config: &ChangeLogConfig,
+    ) -> Result<Vec<(String, String, ConfigSource)>, Error> {
+        let layered = Table::try_from(self.config()?)?;
+        let effective = Table::try_from(config)?;
+
+        let mut origins = Vec::new();
+        for (path, value) in leaves(&effective, Vec::new()) {
+            let source = if !lookup(&layered, &path).is_some_and(|l| same_value(l, value)) {
+                ConfigSource::CommandLine
+            } else {
+                self.layers
+                    .iter()
+                    .rev()
+                    .find(|(_, table)| lookup(table, &path).is_some())
+                    .map_or(ConfigSource::Default, |(source, _)| source.clone())
+            };
+            let key = path
+                .iter()
+                .map(|k| toml_edit::Key::new(k.as_str()).display_repr().to_string())
+                .collect::<Vec<_>>()
+                .join(".");
+            origins.push((key, value.to_string(), source));
+        }
+
+        Ok(origins)
+    }
+}
+
+/// The `gen-changelog` table in the metadata of the `workspace` or `package`
+/// table of a Cargo manifest
+///
+/// The manifest is read as plain TOML, as only the metadata is needed, so a
+/// manifest cargo could not resolve still provides its configuration. A
+/// manifest that is not valid TOML is skipped with a warning.
+fn metadata_table(path: &Path, manifest_table: &str) -> Result<Option<Table>, Error> {
+    let manifest = match read_to_string(path)?.parse::<Table>() {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            log::warn!("ignoring `{}` as it is not valid TOML: {e}", path.display());
+            return Ok(None);
+        }
+    };
+    let Some(value) = manifest
+        .get(manifest_table)
+        .and_then(|t| t.get("metadata"))
+        .and_then(|m| m.get(METADATA_KEY))
+    else {
+        return Ok(None);
+    };
+    Ok(Some(value.clone().try_into()?))
+}
+
+/// Tables replaced whole rather than merged key by key
+fn is_replaced(path: &[&str]) -> bool {
+    matches!(path, ["headings"] | ["display-sections"] | ["groups", _])
+}
+
+fn merge(base: &mut Table, overlay: &Table, path: &[&str]) {
+    for (key, value) in overlay {
+        let mut child = path.to_vec();
+        child.push(key);
+        match (base.get_mut(key), value) {
+            (Some(Value::Table(base)), Value::Table(overlay)) if !is_replaced(&child) => {
+                merge(base, overlay, &child)
+            }
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Values that are not tables, with their path
+fn leaves(table: &Table, path: Vec<String>) -> Vec<(Vec<String>, &Value)> {
+    let mut found = Vec::new();
+    for (key, value) in table {
+        let mut child = path.clone();
+        child.push(key.clone());
+        match value {
+            Value::Table(table) => found.extend(leaves(table, child)),
+            value => found.push((child, value)),
+        }
+    }
+    found
+}
+
+/// Values are the same, in any order for arrays as the commit types of a
+/// group are a set
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().all(|value| b.contains(value))
+        }
+        (a, b) => a == b,
+    }
+}
+
+fn lookup<'a>(table: &'a Table, path: &[String]) -> Option<&'a Value> {
+    let (last, tables) = path.split_last()?;
+    let mut table = table;
+    for key in tables {
+        table = table.get(key)?.as_table()?;
+    }
+    table.get(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn fixture_file(dir: &TempDir, name: &str, content: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn source_of<'a>(origins: &'a [(String, String, ConfigSource)], key: &str) -> &'a ConfigSource {
+        &origins
+            .iter()
+            .find(|(k, _, _)| k == key)
+            .unwrap_or_else(|| panic!("no origin for `{key}`"))
+            .2
+    }
+
+    #[test]
+    fn test_later_layers_override_earlier_layers() {
+        let dir = TempDir::new().expect("create temp dir");
+        let user = fixture_file(
+            &dir,
+            "user/config.toml",
+            "first-parent = true\nunknown-group = \"Other\"\n\n[lint]\nmax-subject-length = 50\n",
+        );
+        let repository = fixture_file(
+            &dir,
+            "gen-changelog.toml",
+            "unknown-group = \"Misc\"\n\n[lint]\nrequire-scope = true\n",
+        );
+        let package = fixture_file(
+            &dir,
+            "crates/foo/gen-changelog.toml",
+            "[headings]\nAdded = 1\n",
+        );
+
+        let mut layers = ConfigLayers::new();
+        layers
+            .add_file(ConfigSource::User(user.clone()))
+            .unwrap()
+            .add_file(ConfigSource::Repository(repository.clone()))
+            .unwrap()
+            .add_file(ConfigSource::Package(package.clone()))
+            .unwrap()
+            .add_file(ConfigSource::Package(dir.path().join("missing.toml")))
+            .unwrap();
+        assert_eq!(layers.sources().len(), 3);
+
+        let config = layers.config().expect("config");
+        assert!(config.first_parent());
+        assert_eq!(config.unknown_group(), "Misc");
+        assert_eq!(config.lint().max_subject_length(), 50);
+        assert!(config.lint().require_scope());
+        assert_eq!(config.headings().values().collect::<Vec<_>>(), ["Added"]);
+
+        let origins = layers.origins(&config).expect("origins");
+        assert_eq!(
+            source_of(&origins, "first-parent"),
+            &ConfigSource::User(user)
+        );
+        assert_eq!(
+            source_of(&origins, "unknown-group"),
+            &ConfigSource::Repository(repository)
+        );
+        assert_eq!(
+            source_of(&origins, "headings.Added"),
+            &ConfigSource::Package(package)
+        );
+        assert_eq!(
+            source_of(&origins, "include-merge-commits"),
+            &ConfigSource::Default
+        );
+    }
+
+    #[test]
+    fn test_cargo_manifest_metadata() {
+        let dir = TempDir::new().expect("create temp dir");
+        let workspace = fixture_file(
+            &dir,
+            "Cargo.toml",
+            r#"[workspace]
+members = ["crates/foo"]
+
+[workspace.metadata.gen-changelog]
+first-parent = true
+unknown-group = "Other"
+
+[workspace.metadata.gen-changelog.lint]
+max-subject-length = 50
+"#,
+        );
+        let repository = fixture_file(&dir, "gen-changelog.toml", "unknown-group = \"Misc\"\n");
+        let package = fixture_file(
+            &dir,
+            "crates/foo/Cargo.toml",
+            r#"[package]
+name = "foo"
+version = "0.1.0"
+edition = "2024"
+
+[package.metadata.gen-changelog.lint]
+require-scope = true
+"#,
+        );
+        fixture_file(&dir, "crates/foo/src/lib.rs", "");
+        let user = fixture_file(
+            &dir,
+            "xdg/gen-changelog/config.toml",
+            "include-merge-commits = true\nfirst-parent = false\n",
+        );
+
+        let layers = ConfigLayers::discover(
+            Some(&dir.path().join("xdg")),
+            dir.path(),
+            Some(&dir.path().join("crates/foo")),
+        )
+        .expect("discover");
+        assert_eq!(layers.sources()[0], &ConfigSource::User(user.clone()));
+        assert!(
+            layers
+                .sources()
+                .contains(&&ConfigSource::WorkspaceMetadata(workspace.clone()))
+        );
+        // The workspace manifest has no package to read
+        assert!(
+            !layers
+                .sources()
+                .contains(&&ConfigSource::PackageMetadata(workspace.clone()))
+        );
+
+        let config = layers.config().expect("config");
+        assert!(config.first_parent());
+        assert_eq!(config.unknown_group(), "Misc");
+        assert_eq!(config.lint().max_subject_length(), 50);
+        assert!(config.lint().require_scope());
+
+        assert!(config.include_merge_commits());
+
+        let origins = layers.origins(&config).expect("origins");
+        assert_eq!(
+            source_of(&origins, "first-parent"),
+            &ConfigSource::WorkspaceMetadata(workspace)
+        );
+        assert_eq!(
+            source_of(&origins, "include-merge-commits"),
+            &ConfigSource::User(user)
+        );
+        assert_eq!(
+            source_of(&origins, "unknown-group"),
+            &ConfigSource::Repository(repository)
+        );
+        assert_eq!(
+            source_of(&origins, "lint.require-scope"),
+            &ConfigSource::PackageMetadata(package)
+        );
+    }
+
+    #[rstest::rstest]
+    #[case("[package]\nname = \"foo\"\nversion.workspace = true\n")]
+    #[case("[package\nname = \"foo\"\n")]
+    fn test_manifest_without_metadata_adds_no_layer(#[case] content: &str) {
+        let dir = TempDir::new().expect("create temp dir");
+        let manifest = fixture_file(&dir, "Cargo.toml", content);
+
+        let mut layers = ConfigLayers::new();
+        layers
+            .add_file(ConfigSource::WorkspaceMetadata(manifest.clone()))
+            .unwrap()
+            .add_file(ConfigSource::PackageMetadata(manifest))
+            .unwrap();
+        assert!(layers.sources().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_manifest_metadata() {
+        let dir = TempDir::new().expect("create temp dir");
+        let manifest = fixture_file(
+            &dir,
+            "Cargo.toml",
+            "[package]\nname = \"foo\"\nversion = \"0.1.0\"\n\n[package.metadata]\ngen-changelog = 1\n",
+        );
+        fixture_file(&dir, "src/lib.rs", "");
+
+        let mut layers = ConfigLayers::new();
+        assert!(matches!(
+            layers.add_file(ConfigSource::PackageMetadata(manifest)),
+            Err(Error::TomlDeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_groups_are_replaced_whole() {
+        let dir = TempDir::new().expect("create temp dir");
+        let user = fixture_file(
+            &dir,
+            "user.toml",
+            "[groups.Perf]\nname = \"Perf\"\npublish = true\ncc-types = [\"perf\"]\n",
+        );
+        let repository = fixture_file(
+            &dir,
+            "gen-changelog.toml",
+            "[groups.Perf]\nname = \"Performance\"\ncc-types = [\"speed\"]\n",
+        );
+
+        let mut layers = ConfigLayers::new();
+        layers
+            .add_file(ConfigSource::User(user))
+            .unwrap()
+            .add_file(ConfigSource::Repository(repository))
+            .unwrap();
+        let config = layers.config().expect("config");
+
+        assert_eq!(
+            config.groups_mapping().get("speed").map(String::as_str),
+            Some("Performance")
+        );
+        assert!(!config.groups_mapping().contains_key("perf"));
+        // The default groups are kept alongside the custom group
+        assert_eq!(
+            config.groups_mapping().get("feat").map(String::as_str),
+            Some("Added")
+        );
+        assert_eq!(
+            config.groups_mapping().get("ci").map(String::as_str),
+            Some("Continuous Integration")
+        );
+
+        let origins = layers.origins(&config).expect("origins");
+        assert_eq!(
+            source_of(&origins, "groups.Added.publish"),
+            &ConfigSource::Default
+        );
+        assert_eq!(
+            source_of(&origins, "groups.Perf.name"),
+            &ConfigSource::Repository(dir.path().join("gen-changelog.toml"))
+        );
+    }
+
+    #[test]
+    fn test_origins_report_command_line_changes() {
+        let layers = ConfigLayers::new();
+        let mut config = layers.config().expect("config");
+        config.set_include_merge_commits(true);
+
+        let origins = layers.origins(&config).expect("origins");
+        assert_eq!(
+            source_of(&origins, "include-merge-commits"),
+            &ConfigSource::CommandLine
+        );
+        assert_eq!(
+            source_of(&origins, r#"groups."Continuous Integration".publish"#),
+            &ConfigSource::Default
+        );
+    }
+}
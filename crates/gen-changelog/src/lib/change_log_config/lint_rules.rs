@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+use crate::change_log_config::glob::glob_match;
+
+/// Default maximum number of characters in the commit subject
+const DEFAULT_MAX_SUBJECT_LENGTH: usize = 72;
+
+/// LintRules configures the checks applied to commit messages by the linter.
+///
+/// Commit types are always checked against the types mapped to groups (see
+/// [`ChangeLogConfig::groups_mapping`](crate::ChangeLogConfig::groups_mapping))
+/// and the footer against the git trailer format. The rules below tune the
+/// remaining checks.
+///
+/// # Example
+///
+/// ```toml
+/// [lint]
+/// max-subject-length = 50
+/// scopes = ["core", "cli", "deps*"]
+/// require-scope = true
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+#[serde(rename_all = "kebab-case")]
+pub struct LintRules {
+    /// Maximum number of characters in the commit subject.
+    max_subject_length: usize,
+    /// Glob patterns for the allowed scopes. When empty any scope is allowed.
+    scopes: Vec<String>,
+    /// Commits must have a scope.
+    require_scope: bool,
+}
+
+impl Default for LintRules {
+    fn default() -> Self {
+        LintRules {
+            max_subject_length: DEFAULT_MAX_SUBJECT_LENGTH,
+            scopes: Vec::new(),
+            require_scope: false,
+        }
+    }
+}
+
+impl LintRules {
+    /// Returns the maximum number of characters in the commit subject.
+    pub fn max_subject_length(&self) -> usize {
+        self.max_subject_length
+    }
+
+    /// Returns the glob patterns for the allowed scopes.
+    pub fn scopes(&self) -> &[String] {
+        &self.scopes
+    }
+
+    /// Returns whether commits must have a scope.
+    pub fn require_scope(&self) -> bool {
+        self.require_scope
+    }
+
+    /// Sets the maximum number of characters in the commit subject.
+    pub fn set_max_subject_length(&mut self, length: usize) -> &mut Self {
+        self.max_subject_length = length;
+        self
+    }
+
+    /// Adds a glob pattern for an allowed scope.
+    pub fn add_scope(&mut self, pattern: &str) -> &mut Self {
+        self.scopes.push(pattern.to_string());
+        self
+    }
+
+    /// Sets whether commits must have a scope.
+    pub fn set_require_scope(&mut self, value: bool) -> &mut Self {
+        self.require_scope = value;
+        self
+    }
+
+    /// Test to determine if the scope is allowed.
+    pub(crate) fn allows_scope(&self, scope: &str) -> bool {
+        let scope = scope.to_lowercase();
+        self.scopes.is_empty()
+            || self
+                .scopes
+                .iter()
+                .any(|pattern| glob_match(&pattern.to_lowercase(), &scope))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LintRules;
+
+    #[test]
+    fn test_allows_scope() {
+        let mut rules = LintRules::default();
+        assert!(rules.allows_scope("anything"));
+
+        rules.add_scope("core").add_scope("deps*");
+        assert!(rules.allows_scope("core"));
+        assert!(rules.allows_scope("Deps-dev"));
+        assert!(!rules.allows_scope("cli"));
+    }
+
+    #[test]
+    fn test_deserialize_from_toml() {
+        let rules: LintRules = toml::from_str(
+            r#"
+            max-subject-length = 50
+            require-scope = true
+            "#,
+        )
+        .expect("deserialize lint rules");
+
+        assert_eq!(rules.max_subject_length(), 50);
+        assert!(rules.scopes().is_empty());
+        assert!(rules.require_scope());
+
+        let rules: LintRules = toml::from_str("").expect("deserialize lint rules");
+        assert_eq!(rules.max_subject_length(), 72);
+    }
+}
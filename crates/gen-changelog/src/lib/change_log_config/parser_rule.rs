@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// ParserRule defines a regular expression used to classify commit summaries
+/// that do not follow the conventional commit format.
+///
+/// The pattern uses named captures to identify the parts of the summary:
+/// - `type`: the commit type used to find the group for the commit
+/// - `scope`: the optional scope of the commit
+/// - `breaking`: matches when the commit is a breaking change
+/// - `description`: the description listed in the changelog; the whole summary
+///   is used when the capture is absent
+///
+/// # Example
+///
+/// ```toml
+/// [[commit-parsers]]
+/// pattern = '^[A-Z]+-\d+ (?P<type>[a-z]+)(?:\((?P<scope>[^)]+)\))?(?P<breaking>!)?: (?P<description>.+)$'
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+#[serde(rename_all = "kebab-case")]
+pub struct ParserRule {
+    /// Regular expression with named captures matched against the summary.
+    pattern: String,
+}
+
+impl ParserRule {
+    /// Creates a rule from a regular expression pattern.
+    pub fn new(pattern: &str) -> Self {
+        ParserRule {
+            pattern: pattern.to_string(),
+        }
+    }
+
+    /// Returns the regular expression pattern of the rule.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+}
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+
+use crate::change_log_config::glob::glob_match;
+
+/// RoutingRule lists conventional commits of a type and scope under a group.
+///
+/// The `type` and `scope` are globs (`*` and `?`) matched case-insensitively
+/// against the commit type and scope. Without a `scope` the rule matches
+/// commits of the type whatever their scope, including none. Rules are tried
+/// in order before the group mapping of the commit type.
+///
+/// # Example
+///
+/// ```toml
+/// [[routing-rules]]
+/// type = "chore"
+/// scope = "deps"
+/// group = "Security"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+#[serde(rename_all = "kebab-case")]
+pub struct RoutingRule {
+    /// Glob pattern for the commit type.
+    #[serde(rename = "type")]
+    kind: String,
+    /// Glob pattern for the commit scope.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+    /// Group that receives the matching commits.
+    group: String,
+}
+
+impl RoutingRule {
+    /// Creates a rule routing commits of the type and, optionally, scope to
+    /// the group.
+    pub fn new(kind: &str, scope: Option<&str>, group: &str) -> Self {
+        RoutingRule {
+            kind: kind.to_string(),
+            scope: scope.map(|s| s.to_string()),
+            group: group.to_string(),
+        }
+    }
+
+    /// Returns the glob pattern for the commit type.
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// Returns the glob pattern for the commit scope, if any.
+    pub fn scope(&self) -> Option<&str> {
+        self.scope.as_deref()
+    }
+
+    /// Returns the group that receives the matching commits.
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
+    /// Test to determine if a commit of the type and scope matches the rule.
+    pub(crate) fn matches(&self, kind: &str, scope: Option<&str>) -> bool {
+        if !glob_match(&self.kind.to_lowercase(), &kind.to_lowercase()) {
+            return false;
+        }
+        match (&self.scope, scope) {
+            (None, _) => true,
+            (Some(pattern), Some(scope)) => {
+                glob_match(&pattern.to_lowercase(), &scope.to_lowercase())
+            }
+            (Some(_), None) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::RoutingRule;
+
+    #[rstest]
+    #[case::type_and_scope("chore", Some("deps"), true)]
+    #[case::case_insensitive("Chore", Some("DEPS"), true)]
+    #[case::other_scope("chore", Some("release"), false)]
+    #[case::no_scope("chore", None, false)]
+    #[case::other_type("fix", Some("deps"), false)]
+    fn test_rule_with_scope(
+        #[case] kind: &str,
+        #[case] scope: Option<&str>,
+        #[case] expected: bool,
+    ) {
+        let rule = RoutingRule::new("chore", Some("deps"), "Security");
+        assert_eq!(rule.matches(kind, scope), expected);
+    }
+
+    #[test]
+    fn test_rule_globs() {
+        let rule = RoutingRule::new("*", Some("deps-*"), "Dependencies");
+        assert!(rule.matches("build", Some("deps-dev")));
+        assert!(!rule.matches("build", Some("deps")));
+
+        // Without a scope pattern every scope matches
+        let rule = RoutingRule::new("perf", None, "Changed");
+        assert!(rule.matches("perf", None));
+        assert!(rule.matches("perf", Some("walk")));
+    }
+
+    #[test]
+    fn test_deserialize_from_toml() {
+        let rule: RoutingRule = toml::from_str(
+            r#"
+            type = "chore"
+            scope = "deps"
+            group = "Security"
+            "#,
+        )
+        .expect("deserialize routing rule");
+
+        assert_eq!(rule, RoutingRule::new("chore", Some("deps"), "Security"));
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// How commits are sub-grouped by scope within a group heading.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScopeMode {
+    /// List the commits of a group without sub-grouping
+    #[default]
+    Off,
+    /// List the commits of each scope under a fourth-level heading
+    Headings,
+    /// Prefix each commit with its scope in bold, sorted by scope
+    Bold,
+}
+
+/// ScopeGroups configures sub-grouping of commits by scope within each group.
+///
+/// Scoped commits are sorted by the position of their scope in `order`, with
+/// scopes not listed following in alphabetical order of their display name.
+/// Commits without a scope are listed first, before any scope sub-heading.
+/// `names` maps raw scopes to the name displayed in the changelog.
+///
+/// # Example
+///
+/// ```toml
+/// [scope-groups]
+/// mode = "headings"
+/// order = ["core", "cli"]
+///
+/// [scope-groups.names]
+/// core = "Core library"
+/// cli = "Command line"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+#[serde(rename_all = "kebab-case")]
+pub struct ScopeGroups {
+    /// How commits are sub-grouped by scope.
+    mode: ScopeMode,
+    /// Raw scopes in the order they are listed.
+    order: Vec<String>,
+    /// Raw scopes mapped to display names.
+    names: BTreeMap<String, String>,
+}
+
+impl ScopeGroups {
+    /// Returns how commits are sub-grouped by scope.
+    pub fn mode(&self) -> ScopeMode {
+        self.mode
+    }
+
+    /// Returns the raw scopes in the order they are listed.
+    pub fn order(&self) -> &[String] {
+        &self.order
+    }
+
+    /// Sets how commits are sub-grouped by scope.
+    pub fn set_mode(&mut self, mode: ScopeMode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the order in which raw scopes are listed.
+    pub fn set_order(&mut self, order: &[&str]) -> &mut Self {
+        self.order = order.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Maps a raw scope to the name displayed in the changelog.
+    pub fn set_name(&mut self, scope: &str, name: &str) -> &mut Self {
+        self.names.insert(scope.to_string(), name.to_string());
+        self
+    }
+
+    /// Name displayed in the changelog for the raw scope.
+    pub fn display_name(&self, scope: &str) -> String {
+        self.names
+            .get(scope)
+            .cloned()
+            .unwrap_or_else(|| scope.to_string())
+    }
+
+    /// Key sorting commits without a scope first, then scopes by the first
+    /// position of their display name in the order and then by display name,
+    /// ignoring case. Scopes sharing a display name get the same key.
+    pub(crate) fn sort_key(&self, scope: Option<&str>) -> (usize, usize, String, String) {
+        let Some(scope) = scope else {
+            return (0, 0, String::new(), String::new());
+        };
+        let name = self.display_name(scope);
+        let position = self
+            .order
+            .iter()
+            .position(|s| self.display_name(s) == name)
+            .unwrap_or(self.order.len());
+        (1, position, name.to_lowercase(), name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_key_orders_scopes() {
+        let mut scope_groups = ScopeGroups::default();
+        scope_groups
+            .set_order(&["core", "cli"])
+            .set_name("api", "Web API");
+
+        let mut scopes = vec![
+            Some("zeta"),
+            Some("cli"),
+            None,
+            Some("api"),
+            Some("core"),
+            Some("alpha"),
+        ];
+        scopes.sort_by_key(|s| scope_groups.sort_key(*s));
+
+        assert_eq!(
+            scopes,
+            [
+                None,
+                Some("core"),
+                Some("cli"),
+                Some("alpha"),
+                Some("api"),
+                Some("zeta")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_from_toml() {
+        let scope_groups: ScopeGroups = toml::from_str(
+            r#"
+            mode = "bold"
+            order = ["core"]
+
+            [names]
+            core = "Core library"
+            "#,
+        )
+        .expect("deserialize scope groups");
+
+        assert_eq!(scope_groups.mode(), ScopeMode::Bold);
+        assert_eq!(scope_groups.order(), ["core".to_string()]);
+        assert_eq!(scope_groups.display_name("core"), "Core library");
+        assert_eq!(scope_groups.display_name("cli"), "cli");
+    }
+}
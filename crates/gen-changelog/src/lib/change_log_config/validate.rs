@@ -0,0 +1,323 @@
+//! Validation of the configuration
+//!
+//! A configuration can be read without error and still not do what was
+//! intended, e.g. when a commit type is listed in two groups. Validation
+//! reports these problems, located in the configuration file when the
+//! configuration was read from one.
+
+use std::{collections::BTreeMap, fmt::Display, ops::Range};
+
+use toml_edit::{Document, Item};
+
+use crate::ChangeLogConfig;
+
+/// Severity of a problem found in the configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The configuration cannot be used as written
+    Error,
+    /// The configuration can be used but is likely not what was intended
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found in the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    severity: Severity,
+    message: String,
+    key: Vec<String>,
+    // 1-based line and column of the problem in the configuration file
+    location: Option<(usize, usize)>,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, key: &[&str], message: String) -> Self {
+        Diagnostic {
+            severity,
+            message,
+            key: key.iter().map(|k| k.to_string()).collect(),
+            location: None,
+        }
+    }
+
+    /// Returns the severity of the problem.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Returns the description of the problem.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the dotted path to the key with the problem, empty when the
+    /// problem is not tied to a key.
+    pub fn key(&self) -> String {
+        self.key
+            .iter()
+            .map(|k| toml_edit::Key::new(k.as_str()).display_repr().to_string())
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// Returns the 1-based line of the problem in the configuration file.
+    pub fn line(&self) -> Option<usize> {
+        self.location.map(|(line, _)| line)
+    }
+
+    /// Returns the 1-based column of the problem in the configuration file.
+    pub fn column(&self) -> Option<usize> {
+        self.location.map(|(_, column)| column)
+    }
+
+    fn locate(mut self, source: &str, span: Option<Range<usize>>) -> Self {
+        self.location = span.map(|span| line_column(source, span.start));
+        self
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some((line, column)) = self.location {
+            write!(f, "{line}:{column}: ")?;
+        }
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+impl ChangeLogConfig {
+    /// Checks the configuration for settings that are unlikely to be intended.
+    ///
+    /// Reports:
+    /// - a commit type listed in more than one group
+    /// - a heading that does not name a group
+    /// - a published group without a heading, so never listed
+    ///
+    /// The diagnostics are not located; use
+    /// [`ChangeLogConfig::validate_str`] to check a configuration file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gen_changelog::ChangeLogConfig;
+    ///
+    /// let config = ChangeLogConfig::default();
+    /// assert!(config.validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        let mut groups = self.groups.iter().collect::<Vec<_>>();
+        groups.sort_by_key(|(key, _)| key.as_str());
+
+        let mut type_groups: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for (key, group) in &groups {
+            for cc_type in group.cc_types() {
+                type_groups.entry(cc_type).or_default().push(key);
+            }
+        }
+        for (cc_type, keys) in type_groups.iter().filter(|(_, keys)| keys.len() > 1) {
+            for key in &keys[1..] {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Error,
+                    &["groups", key, "cc-types"],
+                    format!(
+                        "commit type `{cc_type}` is listed in groups `{}` and `{key}`",
+                        keys[0]
+                    ),
+                ));
+            }
+        }
+
+        for heading in self.headings.values() {
+            if !groups.iter().any(|(_, g)| g.name() == heading) {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Error,
+                    &["headings", heading],
+                    format!("heading `{heading}` does not name a group"),
+                ));
+            }
+        }
+
+        for (key, group) in &groups {
+            if group.publish() && !self.headings.values().any(|h| h == group.name()) {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Warning,
+                    &["groups", key, "publish"],
+                    format!(
+                        "group `{}` is published but has no heading, so it is not listed",
+                        group.name()
+                    ),
+                ));
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Checks the text of a configuration file.
+    ///
+    /// Reports the errors that prevent reading the configuration, or the
+    /// problems found by [`ChangeLogConfig::validate`], located in the text.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gen_changelog::{ChangeLogConfig, Severity};
+    ///
+    /// let diagnostics = ChangeLogConfig::validate_str("[headings]\nFeatures = 1\n");
+    /// assert_eq!(diagnostics[0].severity(), Severity::Error);
+    /// assert_eq!(diagnostics[0].line(), Some(2));
+    /// ```
+    pub fn validate_str(source: &str) -> Vec<Diagnostic> {
+        let config = match toml::from_str::<ChangeLogConfig>(source) {
+            Ok(config) => config,
+            Err(e) => {
+                return vec![
+                    Diagnostic::new(Severity::Error, &[], e.message().trim().to_string())
+                        .locate(source, e.span()),
+                ];
+            }
+        };
+        let document = Document::parse(source).ok();
+
+        config
+            .validate()
+            .into_iter()
+            .map(|diagnostic| {
+                let span = document
+                    .as_ref()
+                    .and_then(|d| key_span(d.as_item(), &diagnostic.key));
+                diagnostic.locate(source, span)
+            })
+            .collect()
+    }
+}
+
+/// Span of the key at the path, or of the closest parent key in the document
+fn key_span(mut item: &Item, path: &[String]) -> Option<Range<usize>> {
+    let mut span = None;
+    for part in path {
+        let Some((key, child)) = item.as_table_like().and_then(|t| t.get_key_value(part)) else {
+            break;
+        };
+        span = key.span().or(span);
+        item = child;
+    }
+    span
+}
+
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |line| line.chars().count())
+        + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"[groups.Fixed]
+name = "Fixed"
+publish = true
+cc-types = ["fix"]
+
+[groups.Patched]
+name = "Patched"
+publish = true
+cc-types = ["fix", "patch"]
+
+[headings]
+Fixed = 1
+Features = 2
+"#;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert!(ChangeLogConfig::default().validate().is_empty());
+    }
+
+    #[test]
+    fn test_validate_str_locates_problems() {
+        let diagnostics = ChangeLogConfig::validate_str(CONFIG);
+        let found = diagnostics
+            .iter()
+            .map(|d| (d.severity(), d.key(), d.line(), d.column()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            found,
+            [
+                (
+                    Severity::Error,
+                    "groups.Patched.cc-types".to_string(),
+                    Some(9),
+                    Some(1)
+                ),
+                (
+                    Severity::Error,
+                    "headings.Features".to_string(),
+                    Some(13),
+                    Some(1)
+                ),
+                (
+                    Severity::Warning,
+                    "groups.Patched.publish".to_string(),
+                    Some(8),
+                    Some(1)
+                ),
+            ]
+        );
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "9:1: error: commit type `fix` is listed in groups `Fixed` and `Patched`"
+        );
+    }
+
+    #[test]
+    fn test_validate_str_reports_parse_error() {
+        let diagnostics =
+            ChangeLogConfig::validate_str("first-parent = true\nfirst-parnet = true\n");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity(), Severity::Error);
+        assert_eq!(diagnostics[0].key(), "");
+        assert_eq!(
+            (diagnostics[0].line(), diagnostics[0].column()),
+            (Some(2), Some(1))
+        );
+        assert!(
+            diagnostics[0]
+                .message()
+                .contains("unknown field `first-parnet`")
+        );
+    }
+
+    #[test]
+    fn test_key_with_spaces_is_quoted() {
+        let mut config = ChangeLogConfig::default();
+        config.define_group("Continuous Delivery", &["ci".to_string()]);
+
+        let diagnostics = config.validate();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].key(),
+            r#"groups."Continuous Integration".cc-types"#
+        );
+        assert_eq!(diagnostics[0].line(), None);
+    }
+}
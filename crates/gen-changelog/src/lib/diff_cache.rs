@@ -0,0 +1,327 @@
+//! Cache of the paths changed by each commit and of its patch-id
+//!
+//! Filtering the commits for a package diffs the tree of every commit against
+//! its parent, as does computing the patch-ids that identify cherry-picked
+//! changes. Both only depend on the commit, so they are cached:
+//! - in memory, shared by every clone of a [`DiffCache`], so changelogs for
+//!   several packages of a workspace diff each commit once
+//! - on disk under the git directory, so later runs do not diff the commits
+//!   seen before
+//!
+//! The file holds a JSON array per line of the commit id, the paths it changed
+//! and its patch-id, either of which is `null` when it has not been computed.
+//! New entries are appended, and the file is rewritten when it holds lines
+//! that are repeated or cannot be read. Merge commits are not cached, as the
+//! paths they change depend on the parent they are compared with.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use git2::{Oid, Repository};
+
+use crate::Error;
+
+/// Path of the cache file relative to the git directory
+const DIFF_CACHE_FILE: &str = "gen-changelog/diff-cache";
+
+/// What is known of the changes made by a commit
+#[derive(Debug, Default, Clone)]
+struct Entry {
+    paths: Option<Vec<PathBuf>>,
+    patch_id: Option<Oid>,
+}
+
+/// A line of the cache file
+type Line = (String, Option<Vec<PathBuf>>, Option<String>);
+
+impl Entry {
+    fn from_line(line: &str) -> Option<(Oid, Entry)> {
+        let (id, paths, patch_id) = serde_json::from_str::<Line>(line).ok()?;
+        let patch_id = match patch_id {
+            Some(patch_id) => Some(Oid::from_str(&patch_id).ok()?),
+            None => None,
+        };
+        Some((Oid::from_str(&id).ok()?, Entry { paths, patch_id }))
+    }
+
+    fn to_line(&self, id: Oid) -> Result<String, Error> {
+        let line: Line = (
+            id.to_string(),
+            self.paths.clone(),
+            self.patch_id.map(|p| p.to_string()),
+        );
+        Ok(serde_json::to_string(&line).map_err(std::io::Error::from)?)
+    }
+
+    /// Adds what the other entry knows
+    fn merge(&mut self, other: Entry) {
+        self.paths = other.paths.or(self.paths.take());
+        self.patch_id = other.patch_id.or(self.patch_id);
+    }
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    /// What is known of each commit
+    commits: HashMap<Oid, Entry>,
+    /// Commits added or completed since the cache was loaded or saved
+    unsaved: HashSet<Oid>,
+    /// The file holds repeated or unreadable lines
+    compact: bool,
+}
+
+/// DiffCache holds the paths changed by each commit and its patch-id.
+///
+/// Clones share the same entries, so a cache passed to the builders of
+/// several changelogs diffs each commit once.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use gen_changelog::{ChangeLog, DiffCache};
+/// use git2::Repository;
+///
+/// # fn main() -> Result<(), gen_changelog::Error> {
+/// let repo = Repository::open(".")?;
+/// let diff_cache = DiffCache::open(&repo)?;
+/// let changelog = ChangeLog::builder()
+///     .with_diff_cache(diff_cache.clone())
+///     .walk_repository(&repo)?
+///     .build();
+/// diff_cache.save()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DiffCache {
+    entries: Arc<Mutex<Entries>>,
+    file: Option<PathBuf>,
+}
+
+impl DiffCache {
+    /// Creates an empty cache held in memory only.
+    pub fn new() -> Self {
+        DiffCache::default()
+    }
+
+    /// Opens the cache stored in the git directory of the repository.
+    ///
+    /// The cache is empty when the file does not exist. Lines that cannot be
+    /// read are skipped, and dropped from the file when the cache is saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read.
+    pub fn open(repository: &Repository) -> Result<Self, Error> {
+        let file = repository.path().join(DIFF_CACHE_FILE);
+        let mut entries = Entries::default();
+
+        if file.exists() {
+            let reader = BufReader::new(fs::File::open(&file)?);
+            for line in reader.lines() {
+                let line = line?;
+                match Entry::from_line(&line) {
+                    Some((id, entry)) => {
+                        if let Some(existing) = entries.commits.get_mut(&id) {
+                            existing.merge(entry);
+                            entries.compact = true;
+                        } else {
+                            entries.commits.insert(id, entry);
+                        }
+                    }
+                    None => {
+                        log::warn!("skipping unreadable diff cache entry `{line}`");
+                        entries.compact = true;
+                    }
+                }
+            }
+        }
+        log::debug!(
+            "loaded {} diff cache entries from `{}`",
+            entries.commits.len(),
+            file.display()
+        );
+
+        Ok(DiffCache {
+            entries: Arc::new(Mutex::new(entries)),
+            file: Some(file),
+        })
+    }
+
+    /// Appends the entries added since the cache was opened or last saved to
+    /// the file, or rewrites the file when it holds repeated or unreadable
+    /// lines. Does nothing for a cache held in memory only.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self) -> Result<(), Error> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+        let mut entries = self.lock();
+        if entries.unsaved.is_empty() && !entries.compact {
+            return Ok(());
+        }
+
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let ids: Vec<Oid> = if entries.compact {
+            entries.commits.keys().copied().collect()
+        } else {
+            entries.unsaved.iter().copied().collect()
+        };
+        let mut buffer = String::new();
+        for id in &ids {
+            buffer.push_str(&entries.commits[id].to_line(*id)?);
+            buffer.push('\n');
+        }
+
+        if entries.compact {
+            // Write a new file and move it into place, so that an interrupted
+            // save does not lose the cache
+            let new_file = file.with_extension("new");
+            fs::write(&new_file, buffer)?;
+            fs::rename(&new_file, file)?;
+        } else {
+            let mut writer = OpenOptions::new().create(true).append(true).open(file)?;
+            writer.write_all(buffer.as_bytes())?;
+        }
+        log::debug!(
+            "saved {} diff cache entries to `{}`",
+            ids.len(),
+            file.display()
+        );
+        entries.unsaved.clear();
+        entries.compact = false;
+
+        Ok(())
+    }
+
+    /// Returns the number of commits in the cache.
+    pub fn len(&self) -> usize {
+        self.lock().commits.len()
+    }
+
+    /// Returns true when the cache holds no commits.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Paths changed by the commit, when cached
+    pub(crate) fn paths(&self, id: Oid) -> Option<Vec<PathBuf>> {
+        self.lock().commits.get(&id)?.paths.clone()
+    }
+
+    /// Patch-id of the commit, when cached
+    pub(crate) fn patch_id(&self, id: Oid) -> Option<Oid> {
+        self.lock().commits.get(&id)?.patch_id
+    }
+
+    /// Caches the paths changed by the commit
+    pub(crate) fn insert_paths(&self, id: Oid, paths: Vec<PathBuf>) {
+        self.insert(
+            id,
+            Entry {
+                paths: Some(paths),
+                patch_id: None,
+            },
+        );
+    }
+
+    /// Caches the patch-id of the commit
+    pub(crate) fn insert_patch_id(&self, id: Oid, patch_id: Oid) {
+        self.insert(
+            id,
+            Entry {
+                paths: None,
+                patch_id: Some(patch_id),
+            },
+        );
+    }
+
+    fn insert(&self, id: Oid, entry: Entry) {
+        let mut entries = self.lock();
+        entries.commits.entry(id).or_default().merge(entry);
+        entries.unsaved.insert(id);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Entries> {
+        // The entries stay consistent if a holder of the lock panicked
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_clones_share_entries() {
+        let cache = DiffCache::new();
+        let clone = cache.clone();
+        let id = Oid::from_str("0123456789abcdef0123456789abcdef01234567").unwrap();
+
+        clone.insert_paths(id, vec![PathBuf::from("src/lib.rs")]);
+
+        assert_eq!(cache.paths(id), Some(vec![PathBuf::from("src/lib.rs")]));
+        assert_eq!(cache.patch_id(id), None);
+        assert_eq!(cache.len(), 1);
+        // Saving a cache held in memory only is a no-op
+        cache.save().expect("save");
+    }
+
+    #[test]
+    fn test_save_and_open() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let repo = Repository::init(temp_dir.path()).expect("init repo");
+        let first = Oid::from_str("0123456789abcdef0123456789abcdef01234567").unwrap();
+        let second = Oid::from_str("89abcdef0123456789abcdef0123456789abcdef").unwrap();
+
+        let cache = DiffCache::open(&repo).expect("open");
+        assert!(cache.is_empty());
+        cache.insert_paths(
+            first,
+            vec![PathBuf::from("a.txt"), PathBuf::from("b c.txt")],
+        );
+        cache.save().expect("save");
+        cache.insert_paths(second, Vec::new());
+        cache.save().expect("save");
+        // Completing an entry appends it again
+        cache.insert_patch_id(first, second);
+        cache.save().expect("save");
+
+        let file = repo.path().join(DIFF_CACHE_FILE);
+        assert_eq!(fs::read_to_string(&file).unwrap().lines().count(), 3);
+        fs::write(
+            &file,
+            format!("{}not json\n", fs::read_to_string(&file).unwrap()),
+        )
+        .unwrap();
+
+        let cache = DiffCache::open(&repo).expect("open");
+        assert_eq!(cache.len(), 2);
+        assert_eq!(
+            cache.paths(first),
+            Some(vec![PathBuf::from("a.txt"), PathBuf::from("b c.txt")])
+        );
+        assert_eq!(cache.patch_id(first), Some(second));
+        assert_eq!(cache.paths(second), Some(Vec::new()));
+        assert_eq!(cache.patch_id(second), None);
+
+        // Saving rewrites the file without the repeated and unreadable lines
+        cache.save().expect("save");
+        assert_eq!(fs::read_to_string(&file).unwrap().lines().count(), 2);
+        let cache = DiffCache::open(&repo).expect("open");
+        assert_eq!(cache.patch_id(first), Some(second));
+        assert_eq!(cache.paths(second), Some(Vec::new()));
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use thiserror::Error;
 
 /// Error messages for the gen-changelog crate
@@ -15,6 +17,39 @@ pub enum Error {
     /// repo not found in capture group
     #[error("repo not found in capture group")]
     RepoNotFound,
+    /// configuration file does not exist
+    #[error("configuration file `{0}` not found")]
+    ConfigNotFound(PathBuf),
+    /// configuration file already exists
+    #[error("configuration file `{0}` already exists")]
+    ConfigExists(PathBuf),
+    /// configuration has problems reported by validation
+    #[error("{0} error(s) found in the configuration")]
+    ConfigInvalid(usize),
+    /// key is not part of the configuration
+    #[error("unknown configuration key `{0}`")]
+    UnknownConfigKey(String),
+    /// group is not defined in the configuration
+    #[error("group `{0}` not found in the configuration")]
+    GroupNotFound(String),
+    /// no git repository could be opened at the path
+    #[error("no git repository found at `{0}`")]
+    RepositoryNotFound(PathBuf, #[source] git2::Error),
+    /// tag cannot be used as a release boundary
+    #[error("tag `{0}` is not a valid release tag")]
+    BadTag(String),
+    /// files changed by a commit could not be listed
+    #[error("failed to diff commit `{0}`")]
+    DiffFailed(String, #[source] git2::Error),
+    /// link to a release is not a valid url
+    #[error("invalid link url `{0}`")]
+    InvalidLinkUrl(String, #[source] url::ParseError),
+    /// hook chained by a previous installation would be replaced
+    #[error("hook `{0}` already exists")]
+    HookExists(PathBuf),
+    /// package named on the command line is not a workspace member
+    #[error("package `{0}` not found in the workspace")]
+    PackageNotFound(String),
     /// no rust package found in repository
     #[error("no rust package found in repository")]
     NoPackageFound,
@@ -30,6 +65,21 @@ pub enum Error {
     /// Error from the toml serializer
     #[error("toml deserializer error: {0}")]
     TomlDeError(#[from] toml::de::Error),
+    /// Error from the toml document parser
+    #[error("toml document error: {0}")]
+    TomlEditError(#[from] toml_edit::TomlError),
+    /// commit parser pattern is not a valid regular expression
+    #[error("invalid commit parser pattern `{0}`")]
+    InvalidParserPattern(String, #[source] lazy_regex::regex::Error),
+    /// commit messages break the lint rules
+    #[error("{0} commit message(s) failed linting")]
+    LintFailed(usize),
+    /// saved changelog differs from the generated changelog
+    #[error("changelog `{0}` is out of date")]
+    ChangeLogOutOfDate(PathBuf),
+    /// no changes found since the latest release
+    #[error("no unreleased changes")]
+    NoUnreleasedChanges,
     /// Error from the cargo_crate
     #[error("cargo toml error: {0}")]
     CargoTomlError(#[from] cargo_toml::Error),
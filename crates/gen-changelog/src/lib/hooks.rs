@@ -0,0 +1,341 @@
+//! Git hook installation
+//!
+//! Writes git hooks that run gen-changelog:
+//! - `commit-msg` lints the commit message against the configuration
+//! - `prepare-commit-msg` adds a template listing the commit types to the
+//!   message of a new commit
+//!
+//! Hooks are written to the directory set by `core.hooksPath` or to the
+//! `hooks` directory of the repository. An existing hook that was not written
+//! by gen-changelog is kept as `<hook>.local` and run first by the new hook.
+//! When `<hook>.local` already exists the installation is refused, unless
+//! forced, so that no hook is lost.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use git2::Repository;
+
+use crate::{ChangeLogConfig, Error};
+
+/// Marker identifying hooks written by gen-changelog
+const HOOK_MARKER: &str = "# Installed by gen-changelog";
+
+/// Suffix for an existing hook that is chained from the installed hook
+const CHAINED_SUFFIX: &str = "local";
+
+/// Git hooks that can be installed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitHook {
+    /// Lints the commit message
+    CommitMsg,
+    /// Adds a commit type template to the commit message
+    PrepareCommitMsg,
+}
+
+impl GitHook {
+    /// Returns the file name git uses for the hook.
+    pub fn name(&self) -> &'static str {
+        match self {
+            GitHook::CommitMsg => "commit-msg",
+            GitHook::PrepareCommitMsg => "prepare-commit-msg",
+        }
+    }
+
+    fn script(&self, config: &ChangeLogConfig) -> String {
+        let command = match self {
+            GitHook::CommitMsg => "gen-changelog lint --message-file \"$1\"".to_string(),
+            GitHook::PrepareCommitMsg => {
+                let types = config
+                    .groups_mapping()
+                    .into_keys()
+                    .chain(config.type_aliases().keys().cloned())
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    r#"# Only add the template to new messages, not to -m, -F, merges or amends
+[ -n "$2" ] && exit 0
+template='# <type>(<scope>)!: <description>
+# Types: {types}'
+{{ head -n 1 "$1"; printf '%s\n' "$template"; tail -n +2 "$1"; }} > "$1.tmp" && mv "$1.tmp" "$1""#
+                )
+            }
+        };
+        let name = self.name();
+
+        format!(
+            r#"#!/bin/sh
+{HOOK_MARKER}: {name}
+hook_dir=$(dirname "$0")
+if [ -x "$hook_dir/{name}.{CHAINED_SUFFIX}" ]; then
+    "$hook_dir/{name}.{CHAINED_SUFFIX}" "$@" || exit $?
+fi
+{command}
+"#
+        )
+    }
+}
+
+/// Outcome of installing a hook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookStatus {
+    /// The hook was written where there was none
+    Installed(PathBuf),
+    /// A hook previously written by gen-changelog was replaced
+    Updated(PathBuf),
+    /// The existing hook was moved to the second path and is run by the hook
+    /// written to the first path
+    Chained(PathBuf, PathBuf),
+}
+
+/// HookInstaller writes gen-changelog git hooks into a repository.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use gen_changelog::{ChangeLogConfig, GitHook, HookInstaller};
+/// use git2::Repository;
+///
+/// # fn main() -> Result<(), gen_changelog::Error> {
+/// let repo = Repository::open(".")?;
+/// let installer = HookInstaller::new(&repo)?;
+/// installer.install(GitHook::CommitMsg, &ChangeLogConfig::default())?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HookInstaller {
+    dir: PathBuf,
+    force: bool,
+}
+
+impl HookInstaller {
+    /// Creates an installer for the hooks directory of the repository.
+    ///
+    /// The directory is taken from `core.hooksPath`, relative to the working
+    /// directory when not absolute, or defaults to the `hooks` directory
+    /// inside the git directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the repository configuration cannot be read.
+    pub fn new(repository: &Repository) -> Result<Self, Error> {
+        let hooks_path = match repository.config()?.get_path("core.hooksPath") {
+            Ok(path) => Some(path),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        let dir = match hooks_path {
+            Some(path) if path.is_absolute() => path,
+            Some(path) => repository
+                .workdir()
+                .unwrap_or_else(|| repository.path())
+                .join(path),
+            None => repository.path().join("hooks"),
+        };
+        log::debug!("hooks directory: `{}`", dir.display());
+
+        Ok(HookInstaller { dir, force: false })
+    }
+
+    /// Sets whether an existing `<hook>.local` is replaced when chaining a
+    /// hook.
+    ///
+    /// # Arguments
+    ///
+    /// * `force` - true to replace the file, false to refuse the installation
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining
+    pub fn set_force(&mut self, force: bool) -> &mut Self {
+        self.force = force;
+        self
+    }
+
+    /// Returns the directory the hooks are written to.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Writes the hook, chaining any existing hook not written by
+    /// gen-changelog.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::HookExists`] if the existing hook would replace an
+    /// earlier chained hook and the installer is not forced, or an error if
+    /// the hook cannot be written.
+    pub fn install(&self, hook: GitHook, config: &ChangeLogConfig) -> Result<HookStatus, Error> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(hook.name());
+
+        let status = if !path.exists() {
+            HookStatus::Installed(path.clone())
+        } else if fs::read_to_string(&path).is_ok_and(|s| s.contains(HOOK_MARKER)) {
+            HookStatus::Updated(path.clone())
+        } else {
+            let chained = self.dir.join(format!("{}.{CHAINED_SUFFIX}", hook.name()));
+            if chained.exists() {
+                if !self.force {
+                    return Err(Error::HookExists(chained));
+                }
+                log::warn!(
+                    "`{}` already exists and is replaced by `{}`",
+                    chained.display(),
+                    path.display()
+                );
+            }
+            fs::rename(&path, &chained)?;
+            HookStatus::Chained(path.clone(), chained)
+        };
+
+        fs::write(&path, hook.script(config))?;
+        make_executable(&path)?;
+        log::debug!("{status:?}");
+
+        Ok(status)
+    }
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), Error> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = fs::metadata(path)?.permissions();
+    permissions.set_mode(0o755);
+    fs::set_permissions(path, permissions)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), Error> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn fixture_repo() -> (TempDir, Repository) {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let repo = Repository::init(temp_dir.path()).expect("init repo");
+        (temp_dir, repo)
+    }
+
+    #[test]
+    fn test_install_into_git_hooks() {
+        let (_td, repo) = fixture_repo();
+        let installer = HookInstaller::new(&repo).expect("installer");
+        assert_eq!(installer.dir(), repo.path().join("hooks"));
+
+        let config = ChangeLogConfig::default();
+        let path = repo.path().join("hooks/commit-msg");
+        let status = installer
+            .install(GitHook::CommitMsg, &config)
+            .expect("install");
+        assert_eq!(status, HookStatus::Installed(path.clone()));
+
+        let script = fs::read_to_string(&path).unwrap();
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("gen-changelog lint --message-file \"$1\""));
+
+        // Installing again replaces our own hook without chaining it
+        let status = installer
+            .install(GitHook::CommitMsg, &config)
+            .expect("install");
+        assert_eq!(status, HookStatus::Updated(path));
+        assert!(!repo.path().join("hooks/commit-msg.local").exists());
+    }
+
+    #[test]
+    fn test_install_chains_existing_hook() {
+        let (_td, repo) = fixture_repo();
+        let hooks = repo.path().join("hooks");
+        fs::create_dir_all(&hooks).unwrap();
+        fs::write(
+            hooks.join("prepare-commit-msg"),
+            "#!/bin/sh\necho existing\n",
+        )
+        .unwrap();
+
+        let installer = HookInstaller::new(&repo).expect("installer");
+        let status = installer
+            .install(GitHook::PrepareCommitMsg, &ChangeLogConfig::default())
+            .expect("install");
+
+        assert_eq!(
+            status,
+            HookStatus::Chained(
+                hooks.join("prepare-commit-msg"),
+                hooks.join("prepare-commit-msg.local")
+            )
+        );
+        assert_eq!(
+            fs::read_to_string(hooks.join("prepare-commit-msg.local")).unwrap(),
+            "#!/bin/sh\necho existing\n"
+        );
+        let script = fs::read_to_string(hooks.join("prepare-commit-msg")).unwrap();
+        assert!(script.contains("\"$hook_dir/prepare-commit-msg.local\" \"$@\" || exit $?"));
+        assert!(script.contains("# Types: "));
+        assert!(script.contains("docs, feat, fix"));
+    }
+
+    #[test]
+    fn test_install_keeps_existing_chained_hook() {
+        let (_td, repo) = fixture_repo();
+        let hooks = repo.path().join("hooks");
+        fs::create_dir_all(&hooks).unwrap();
+        fs::write(hooks.join("commit-msg"), "#!/bin/sh\necho second\n").unwrap();
+        fs::write(hooks.join("commit-msg.local"), "#!/bin/sh\necho first\n").unwrap();
+
+        let mut installer = HookInstaller::new(&repo).expect("installer");
+        let config = ChangeLogConfig::default();
+        assert!(matches!(
+            installer.install(GitHook::CommitMsg, &config),
+            Err(Error::HookExists(path)) if path == hooks.join("commit-msg.local")
+        ));
+        assert_eq!(
+            fs::read_to_string(hooks.join("commit-msg")).unwrap(),
+            "#!/bin/sh\necho second\n"
+        );
+        assert_eq!(
+            fs::read_to_string(hooks.join("commit-msg.local")).unwrap(),
+            "#!/bin/sh\necho first\n"
+        );
+
+        let status = installer
+            .set_force(true)
+            .install(GitHook::CommitMsg, &config)
+            .expect("install");
+        assert!(matches!(status, HookStatus::Chained(..)));
+        assert_eq!(
+            fs::read_to_string(hooks.join("commit-msg.local")).unwrap(),
+            "#!/bin/sh\necho second\n"
+        );
+    }
+
+    #[test]
+    fn test_install_into_core_hooks_path() {
+        let (td, repo) = fixture_repo();
+        repo.config()
+            .unwrap()
+            .set_str("core.hooksPath", ".githooks")
+            .unwrap();
+
+        let installer = HookInstaller::new(&repo).expect("installer");
+        installer
+            .install(GitHook::CommitMsg, &ChangeLogConfig::default())
+            .expect("install");
+
+        assert!(td.path().join(".githooks/commit-msg").exists());
+    }
+}
@@ -6,13 +6,23 @@
 
 mod change_log;
 mod change_log_config;
+mod diff_cache;
 mod error;
+mod hooks;
+mod lint;
 mod package;
 
 #[cfg(test)]
 pub(crate) mod test_utils;
 
 pub use change_log::{ChangeLog, ChangeLogBuilder, DEFAULT_CHANGELOG_FILENAME};
-pub use change_log_config::{ChangeLogConfig, ReleasePattern};
+pub use change_log_config::{
+    AuthorFilter, ChangeLogConfig, CherryPickPolicy, ConfigDocument, ConfigLayers, ConfigSource,
+    Diagnostic, EmojiRender, Gitmoji, LintRules, ParserRule, ReleasePattern, RoutingRule,
+    ScopeGroups, ScopeMode, Severity,
+};
+pub use diff_cache::DiffCache;
 pub use error::Error;
+pub use hooks::{GitHook, HookInstaller, HookStatus};
+pub use lint::{LintReport, Linter, Violation};
 pub use package::RustPackages;
@@ -0,0 +1,490 @@
+//! Commit message linting
+//!
+//! Checks commit messages against the conventions the changelog generator
+//! relies on:
+//! - `header-format`: the subject is a conventional commit, or is classified
+//!   by a custom commit parser, gitmoji or `Changelog-Group` trailer
+//! - `type-enum`: the commit type, after aliases, is mapped to a group
+//! - `scope-enum`: the scope matches one of the configured scopes
+//! - `scope-empty`: the commit has a scope when scopes are required
+//! - `subject-max-length`: the subject fits the configured length
+//! - `body-leading-blank`: the subject is followed by a blank line
+//! - `footer-format`: every line of the footer is a `Token: value` trailer
+//!
+//! Merge commits and `fixup!`/`squash!` commits are not checked.
+
+use std::collections::BTreeSet;
+
+use git2::{DescribeFormatOptions, DescribeOptions, ErrorClass, Repository, Sort};
+use lazy_regex::{Lazy, Regex, lazy_regex};
+use serde::Serialize;
+
+use crate::{
+    ChangeLogConfig, Error,
+    change_log::section::{cc_commit::ConvCommit, commit_parser::CommitParser},
+};
+
+/// First line of a git trailer or conventional commit footer.
+static TRAILER: Lazy<Regex> =
+    lazy_regex!(r"^(?:BREAKING[ -]CHANGE|[A-Za-z][A-Za-z0-9-]*)(?:: | #)");
+
+/// Scissors line below which git ignores the message in `commit --verbose`.
+static SCISSORS: Lazy<Regex> = lazy_regex!(r"(?m)^# -+ >8 -+$");
+
+/// A rule broken by a commit message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    rule: String,
+    message: String,
+}
+
+impl Violation {
+    fn new(rule: &str, message: String) -> Self {
+        Violation {
+            rule: rule.to_string(),
+            message,
+        }
+    }
+
+    /// Returns the identifier of the rule that was broken.
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+
+    /// Returns the description of the violation.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// The result of linting a single commit message.
+#[derive(Debug, Clone, Serialize)]
+pub struct LintReport {
+    /// The id of the commit; absent when linting a message file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commit: Option<String>,
+    subject: String,
+    violations: Vec<Violation>,
+}
+
+impl LintReport {
+    /// Returns the id of the commit, if the message came from a commit.
+    pub fn commit(&self) -> Option<&str> {
+        self.commit.as_deref()
+    }
+
+    /// Returns the subject line of the commit message.
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Returns the rules broken by the commit message.
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// Returns true when the commit message breaks no rules.
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Linter checks commit messages against the configuration.
+///
+/// # Example
+///
+/// ```rust
+/// use gen_changelog::{ChangeLogConfig, Linter};
+///
+/// # fn main() -> Result<(), gen_changelog::Error> {
+/// let linter = Linter::new(&ChangeLogConfig::default())?;
+///
+/// assert!(linter.lint_message("feat(cli): add lint command").is_valid());
+/// assert!(!linter.lint_message("wip: add lint command").is_valid());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Linter {
+    config: ChangeLogConfig,
+    parser: CommitParser,
+}
+
+impl Linter {
+    /// Creates a linter for the rules in the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a custom commit parser pattern is invalid.
+    pub fn new(config: &ChangeLogConfig) -> Result<Self, Error> {
+        Ok(Linter {
+            config: config.clone(),
+            parser: CommitParser::new(config)?,
+        })
+    }
+
+    /// Lints the commits in a revision range.
+    ///
+    /// The range is either `<from>..<to>`, listing the commits reachable from
+    /// `to` but not `from`, or a single revision, listing the revision and all
+    /// its ancestors. Commits are reported oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range cannot be resolved or a commit cannot be
+    /// read.
+    pub fn lint_range(
+        &self,
+        repository: &Repository,
+        range: &str,
+    ) -> Result<Vec<LintReport>, Error> {
+        let mut revwalk = repository.revwalk()?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        if range.contains("..") {
+            revwalk.push_range(range)?;
+        } else {
+            revwalk.push(repository.revparse_single(range)?.peel_to_commit()?.id())?;
+        }
+
+        let mut reports = Vec::new();
+        for oid in revwalk {
+            let commit = repository.find_commit(oid?)?;
+            if commit.parent_count() > 1 {
+                log::debug!("Skipping merge commit `{}`", commit.id());
+                continue;
+            }
+            let mut report = self.lint_message(commit.message()?);
+            report.commit = Some(commit.id().to_string());
+            reports.push(report);
+        }
+        Ok(reports)
+    }
+
+    /// Returns the range of the commits that have not been pushed or released.
+    ///
+    /// The range is `@{upstream}..HEAD` when the current branch tracks an
+    /// upstream branch, otherwise the commits since the latest tag in the
+    /// history of `HEAD`, or all of `HEAD` when there is no tag.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `HEAD` cannot be resolved or described.
+    pub fn default_range(repository: &Repository) -> Result<String, Error> {
+        match repository.revparse_single("@{upstream}") {
+            Ok(_) => return Ok("@{upstream}..HEAD".to_string()),
+            Err(e) => log::debug!("no upstream branch: {e}"),
+        }
+
+        let head = repository.head()?.peel_to_commit()?;
+        let describe = match head
+            .as_object()
+            .describe(DescribeOptions::new().describe_tags())
+        {
+            Ok(describe) => describe,
+            Err(e) if e.class() == ErrorClass::Describe => {
+                log::debug!("no tag in the history: {e}");
+                return Ok("HEAD".to_string());
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let tag = describe.format(Some(DescribeFormatOptions::new().abbreviated_size(0)))?;
+        Ok(format!("{tag}..HEAD"))
+    }
+
+    /// Lints a commit message as written by git to the `commit-msg` hook.
+    ///
+    /// Comment lines starting with `#` and everything below the scissors line
+    /// are ignored.
+    pub fn lint_message(&self, message: &str) -> LintReport {
+        let message = strip_comments(message);
+        let mut lines = message.lines();
+        let subject = lines.next().unwrap_or_default().trim_end().to_string();
+        let body = lines.collect::<Vec<_>>().join("\n");
+
+        let violations = if subject.starts_with("Merge ")
+            || subject.starts_with("fixup! ")
+            || subject.starts_with("squash! ")
+        {
+            log::debug!("Skipping `{subject}`");
+            Vec::new()
+        } else {
+            self.check(&subject, &body)
+        };
+
+        LintReport {
+            commit: None,
+            subject,
+            violations,
+        }
+    }
+
+    fn check(&self, subject: &str, body: &str) -> Vec<Violation> {
+        let rules = self.config.lint();
+        let mut violations = Vec::new();
+
+        let length = subject.chars().count();
+        if length > rules.max_subject_length() {
+            violations.push(Violation::new(
+                "subject-max-length",
+                format!(
+                    "subject is {length} characters long, more than {}",
+                    rules.max_subject_length()
+                ),
+            ));
+        }
+
+        if body.lines().next().is_some_and(|l| !l.trim().is_empty()) {
+            violations.push(Violation::new(
+                "body-leading-blank",
+                "the subject must be followed by a blank line".to_string(),
+            ));
+        }
+
+        let commit = ConvCommit::with_parser(Some(subject), Some(body.trim_start()), &self.parser);
+        if let Some(kind) = commit.kind() {
+            self.check_kind(&commit, &kind, &mut violations);
+        } else if commit.group().is_none() {
+            violations.push(Violation::new(
+                "header-format",
+                "subject is not a conventional commit, e.g. `feat(scope): description`".to_string(),
+            ));
+        }
+
+        check_footer(body, &mut violations);
+
+        violations
+    }
+
+    fn check_kind(&self, commit: &ConvCommit, kind: &str, violations: &mut Vec<Violation>) {
+        if commit.is_revert() {
+            return;
+        }
+        let rules = self.config.lint();
+        let scope = commit.scope();
+        let normalised = self.config.normalise_type(kind);
+
+        let routed = self
+            .config
+            .routing_rules()
+            .iter()
+            .any(|rule| rule.matches(&normalised, scope.as_deref()));
+        let types = self.config.groups_mapping();
+        if commit.group().is_none() && !routed && !types.contains_key(&normalised) {
+            let known = types
+                .keys()
+                .chain(self.config.type_aliases().keys())
+                .map(|k| k.as_str())
+                .collect::<BTreeSet<_>>();
+            violations.push(Violation::new(
+                "type-enum",
+                format!(
+                    "type `{kind}` is not one of: {}",
+                    known.into_iter().collect::<Vec<_>>().join(", ")
+                ),
+            ));
+        }
+
+        match scope {
+            Some(scope) if !rules.allows_scope(&scope) => violations.push(Violation::new(
+                "scope-enum",
+                format!(
+                    "scope `{scope}` is not one of: {}",
+                    rules.scopes().join(", ")
+                ),
+            )),
+            None if rules.require_scope() => violations.push(Violation::new(
+                "scope-empty",
+                "a scope is required, e.g. `feat(scope): description`".to_string(),
+            )),
+            _ => {}
+        }
+    }
+}
+
+/// Check that the last paragraph of the body, when it starts with a trailer,
+/// is made up only of trailers and their continuation lines
+fn check_footer(body: &str, violations: &mut Vec<Violation>) {
+    let Some(footer) = body
+        .trim()
+        .split("\n\n")
+        .last()
+        .filter(|p| TRAILER.is_match(p))
+    else {
+        return;
+    };
+
+    for line in footer.lines() {
+        if !TRAILER.is_match(line) && !line.starts_with([' ', '\t']) {
+            violations.push(Violation::new(
+                "footer-format",
+                format!("footer line `{line}` is not a `Token: value` trailer"),
+            ));
+        }
+    }
+}
+
+/// Remove comment lines and anything below the scissors line
+fn strip_comments(message: &str) -> String {
+    let message = match SCISSORS.find(message) {
+        Some(m) => &message[..m.start()],
+        None => message,
+    };
+    message
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_start()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use git2::Signature;
+    use rstest::rstest;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::LintRules;
+
+    fn rules_for(message: &str, config: &ChangeLogConfig) -> Vec<String> {
+        Linter::new(config)
+            .expect("valid config")
+            .lint_message(message)
+            .violations()
+            .iter()
+            .map(|v| v.rule().to_string())
+            .collect()
+    }
+
+    #[rstest]
+    #[case::valid("feat(cli): add lint command", &[])]
+    #[case::case("FEAT: add lint command", &[])]
+    #[case::alias("Feature: add lint command", &["type-enum"])]
+    #[case::routed("chore(deps): bump git2", &[])]
+    #[case::revert("Revert \"feat: add lint command\"", &[])]
+    #[case::merge("Merge branch 'main' into topic", &[])]
+    #[case::fixup("fixup! feat: add lint command", &[])]
+    #[case::not_conventional("Add lint command", &["header-format"])]
+    #[case::unknown_type("wip: add lint command", &["type-enum"])]
+    #[case::too_long(
+        "feat: add a lint command that checks every commit message in the range given",
+        &["subject-max-length"]
+    )]
+    #[case::no_blank_line("feat: add lint\nchecks the messages", &["body-leading-blank"])]
+    #[case::footer(
+        "feat: add lint\n\nBody text.\n\nRefs #42\nReviewed by: Jane\nSigned-off-by: Jane <jane@example.com>",
+        &["footer-format"]
+    )]
+    #[case::valid_footer(
+        "feat: add lint\n\nBody text.\n\nBREAKING CHANGE: replaces the check\n  command entirely\nRefs #42",
+        &[]
+    )]
+    #[case::comments(
+        "# Please enter the commit message\nfeat: add lint\n# ------------------------ >8 ------------------------\ndiff --git a/x b/x",
+        &[]
+    )]
+    fn test_lint_message(#[case] message: &str, #[case] expected: &[&str]) {
+        assert_eq!(rules_for(message, &ChangeLogConfig::default()), expected);
+    }
+
+    #[test]
+    fn test_lint_type_alias() {
+        let mut config = ChangeLogConfig::default();
+        config.add_type_alias("feature", "feat");
+
+        assert!(rules_for("Feature: add lint command", &config).is_empty());
+    }
+
+    #[test]
+    fn test_lint_scope_rules() {
+        let mut rules = LintRules::default();
+        rules.add_scope("core").set_require_scope(true);
+        let mut config = ChangeLogConfig::default();
+        config.set_lint(rules);
+
+        assert!(rules_for("fix(core): walk tags", &config).is_empty());
+        assert_eq!(rules_for("fix(cli): quote paths", &config), ["scope-enum"]);
+        assert_eq!(rules_for("fix: typo", &config), ["scope-empty"]);
+    }
+
+    #[test]
+    fn test_lint_range() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let repo = Repository::init(temp_dir.path()).expect("init repo");
+        let sig = Signature::now("Jane Doe", "jane@example.com").expect("signature");
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+
+        let mut parent = None;
+        let mut ids = Vec::new();
+        for message in ["feat: first", "wip: second", "fix: third"] {
+            let parents = parent.iter().collect::<Vec<_>>();
+            let id = repo
+                .commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+                .expect("commit");
+            parent = Some(repo.find_commit(id).unwrap());
+            ids.push(id);
+        }
+
+        let linter = Linter::new(&ChangeLogConfig::default()).expect("valid config");
+
+        let reports = linter.lint_range(&repo, "HEAD").expect("lint range");
+        let subjects = reports.iter().map(|r| r.subject()).collect::<Vec<_>>();
+        assert_eq!(subjects, ["feat: first", "wip: second", "fix: third"]);
+        assert!(!reports[1].is_valid());
+        assert_eq!(reports[1].commit(), Some(ids[1].to_string().as_str()));
+
+        let range = format!("{}..HEAD", ids[0]);
+        let reports = linter.lint_range(&repo, &range).expect("lint range");
+        assert_eq!(reports.len(), 2);
+
+        let json = serde_json::to_value(&reports[0]).expect("serialize report");
+        assert_eq!(json["subject"], "wip: second");
+        assert_eq!(json["violations"][0]["rule"], "type-enum");
+    }
+
+    #[test]
+    fn test_default_range() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let repo = Repository::init(temp_dir.path()).expect("init repo");
+        let sig = Signature::now("Jane Doe", "jane@example.com").expect("signature");
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let commit = |message: &str| {
+            let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+            let parents = parent.iter().collect::<Vec<_>>();
+            repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+                .expect("commit")
+        };
+
+        let released = commit("feat: first");
+        assert_eq!(Linter::default_range(&repo).expect("range"), "HEAD");
+
+        let object = repo.find_object(released, None).unwrap();
+        repo.tag_lightweight("v0.1.0", &object, false).unwrap();
+        let pushed = commit("fix: second");
+        commit("fix: third");
+        assert_eq!(Linter::default_range(&repo).expect("range"), "v0.1.0..HEAD");
+
+        repo.reference("refs/remotes/origin/main", pushed, false, "fetch")
+            .unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        let mut config = repo.config().unwrap();
+        config
+            .set_str(&format!("branch.{branch}.remote"), "origin")
+            .unwrap();
+        config
+            .set_str(&format!("branch.{branch}.merge"), "refs/heads/main")
+            .unwrap();
+        repo.remote("origin", "https://github.com/jerus-org/fixture.git")
+            .unwrap();
+        let range = Linter::default_range(&repo).expect("range");
+        assert_eq!(range, "@{upstream}..HEAD");
+
+        let linter = Linter::new(&ChangeLogConfig::default()).expect("valid config");
+        let reports = linter.lint_range(&repo, &range).expect("lint range");
+        let subjects = reports.iter().map(|r| r.subject()).collect::<Vec<_>>();
+        assert_eq!(subjects, ["fix: third"]);
+    }
+}
@@ -1,4 +1,68 @@
+/// User config directory for the tests, so the user's own configuration is
+/// not read
+const XDG_CONFIG_HOME: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/xdg");
+
 #[test]
 fn cli_tests() {
-    trycmd::TestCases::new().case("tests/cmd/*.trycmd");
+    trycmd::TestCases::new()
+        .env("XDG_CONFIG_HOME", XDG_CONFIG_HOME)
+        .case("tests/cmd/*.trycmd");
+}
+
+/// Creates a workspace with a `foo` package and one commit to it
+fn fixture_workspace() -> tempfile::TempDir {
+    let temp_dir = tempfile::TempDir::new().expect("create temp dir");
+    let files = [
+        (
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/foo\"]\nresolver = \"3\"\n",
+        ),
+        (
+            "crates/foo/Cargo.toml",
+            "[package]\nname = \"foo\"\nversion = \"0.1.0\"\nedition = \"2024\"\n",
+        ),
+        ("crates/foo/src/lib.rs", ""),
+    ];
+    for (name, content) in files {
+        let path = temp_dir.path().join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    let repo = git2::Repository::init(temp_dir.path()).expect("init repo");
+    repo.remote("origin", "https://github.com/jerus-org/fixture.git")
+        .expect("add remote");
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = git2::Signature::now("Jane Doe", "jane@example.com").unwrap();
+    repo.commit(Some("HEAD"), &sig, &sig, "feat: add foo", &tree, &[])
+        .unwrap();
+
+    temp_dir
+}
+
+#[test]
+fn generate_with_unwritable_diff_cache() {
+    let temp_dir = fixture_workspace();
+    // A file where the cache directory should be stops the cache being saved
+    std::fs::write(temp_dir.path().join(".git/gen-changelog"), "").unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_gen-changelog"))
+        .args(["-v", "generate", "--package", "foo", "--no-save", "--show"])
+        .arg("--repository-dir")
+        .arg(temp_dir.path())
+        .env("XDG_CONFIG_HOME", XDG_CONFIG_HOME)
+        .output()
+        .expect("run gen-changelog");
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("add foo"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("failed to save the diff cache"));
 }